@@ -1,6 +1,6 @@
-use crate::util::TupleArrayDisplay;
-use crate::{write_str_variable, write_variable};
-use std::{collections, fs, io, path};
+use crate::util::{ArrayDisplay, TupleArrayDisplay};
+use crate::{write_str_variable, write_variable, BuiltWriter};
+use std::{collections, fs, io, path, process};
 
 fn package_names<'a, I>(packages: I) -> Vec<(String, String)>
 where
@@ -16,6 +16,58 @@ where
     res
 }
 
+/// Classifies where a package comes from; packages without a source are part of the
+/// workspace or path-dependencies.
+fn source_kind(package: &cargo_lock::Package) -> &'static str {
+    match &package.source {
+        None => "path",
+        Some(source) if source.is_path() => "path",
+        Some(source) if source.is_git() => "git",
+        Some(_) => "registry",
+    }
+}
+
+fn package_sources<'a, I>(packages: I) -> Vec<(String, String, &'static str)>
+where
+    I: IntoIterator<Item = &'a cargo_lock::Package>,
+{
+    let mut res = packages
+        .into_iter()
+        .map(|package| {
+            (
+                package.name.to_string(),
+                package.version.to_string(),
+                source_kind(package),
+            )
+        })
+        .collect::<collections::HashSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    res.sort_unstable();
+    res
+}
+
+fn write_dependency_sources(
+    w: &mut BuiltWriter<'_>,
+    sources: &[(String, String, &'static str)],
+) -> io::Result<()> {
+    write_variable!(
+        w,
+        "DEPENDENCIES_WITH_SOURCE",
+        format_args!("[(&str, &str, &str); {}]", sources.len()),
+        ArrayDisplay(sources, |(n, v, s), f| write!(
+            f,
+            "(\"{}\", \"{}\", \"{}\")",
+            n.escape_default(),
+            v.escape_default(),
+            s
+        )),
+        "The effective dependencies as `(name, version, source)`-tuples, where `source` \
+        is one of `registry`, `git` or `path`."
+    );
+    Ok(())
+}
+
 fn find_lockfile(base: &path::Path) -> io::Result<path::PathBuf> {
     base.ancestors()
         .find_map(|p| {
@@ -25,9 +77,250 @@ fn find_lockfile(base: &path::Path) -> io::Result<path::PathBuf> {
         .ok_or(io::Error::other("Cargo.lock not found"))
 }
 
+/// Fails if `Cargo.lock` is out of date with respect to the manifest in `manifest_location`.
+///
+/// This runs `cargo metadata --locked` using the `cargo`-binary `cargo`; if it fails for any
+/// other reason, e.g. because it can't reach the network, the check is skipped with a warning.
+pub fn check_locked(
+    manifest_location: &path::Path,
+    cargo: &path::Path,
+    w: &BuiltWriter<'_>,
+) -> io::Result<()> {
+    let output = process::Command::new(cargo)
+        .args([
+            "metadata",
+            "--format-version",
+            "1",
+            "--locked",
+            "--manifest-path",
+        ])
+        .arg(manifest_location.join("Cargo.toml"))
+        .stdout(process::Stdio::null())
+        .output()?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.contains("--locked") {
+        return Err(io::Error::other(format!(
+            "Cargo.lock is out of date: {}",
+            stderr.trim()
+        )));
+    }
+    w.directive(format_args!(
+        "warning=Could not check whether Cargo.lock is up to date: {}",
+        stderr.lines().next().unwrap_or_default()
+    ));
+    Ok(())
+}
+
+/// Writes the dependencies as resolved by `cargo metadata` for the current target and the
+/// features the package is compiled with, following only normal dependencies.
+#[cfg(feature = "cargo-metadata")]
+pub fn write_resolved_dependencies(
+    manifest_location: &path::Path,
+    envmap: &crate::environment::EnvironmentMap,
+    cargo: &path::Path,
+    with_versions: bool,
+    max_dependencies: Option<usize>,
+    w: &mut BuiltWriter<'_>,
+) -> io::Result<()> {
+    use cargo_metadata::{CargoOpt, DependencyKind, MetadataCommand};
+
+    let manifest_path = manifest_location.join("Cargo.toml");
+    let features = match envmap.get("CARGO_CFG_FEATURE") {
+        Some(features) => features
+            .split(',')
+            .filter(|f| !f.is_empty())
+            .map(|f| f.to_owned())
+            .collect(),
+        None => envmap
+            .filter_map_keys(|k| k.strip_prefix("CARGO_FEATURE_"))
+            .map(|f| f.to_lowercase().replace('_', "-"))
+            .collect::<Vec<_>>(),
+    };
+    let mut cmd = MetadataCommand::new();
+    cmd.cargo_path(cargo)
+        .manifest_path(&manifest_path)
+        .features(CargoOpt::NoDefaultFeatures)
+        .features(CargoOpt::SomeFeatures(features));
+    if let Some(target) = envmap.get("TARGET") {
+        cmd.other_options(vec!["--filter-platform".to_owned(), target.to_owned()]);
+    }
+    let metadata = cmd.exec().map_err(io::Error::other)?;
+    w.rerun_if_changed(manifest_path.clone());
+    if let Ok(lockfile_path) = find_lockfile(manifest_location) {
+        w.rerun_if_changed(lockfile_path);
+    }
+
+    let root = metadata
+        .packages
+        .iter()
+        .find(|p| p.manifest_path.as_std_path() == manifest_path)
+        .ok_or_else(|| io::Error::other("Package not found in `cargo metadata`"))?;
+    let resolve = metadata
+        .resolve
+        .as_ref()
+        .ok_or_else(|| io::Error::other("No dependency-graph in `cargo metadata`"))?;
+    let nodes = resolve
+        .nodes
+        .iter()
+        .map(|node| (&node.id, node))
+        .collect::<collections::HashMap<_, _>>();
+    let packages = metadata
+        .packages
+        .iter()
+        .map(|package| (&package.id, package))
+        .collect::<collections::HashMap<_, _>>();
+    let normal_deps = |id| {
+        nodes.get(id).into_iter().flat_map(|node| {
+            node.deps
+                .iter()
+                .filter(|dep| {
+                    dep.dep_kinds
+                        .iter()
+                        .any(|info| info.kind == DependencyKind::Normal)
+                })
+                .map(|dep| &dep.pkg)
+        })
+    };
+
+    let direct = normal_deps(&root.id).collect::<collections::HashSet<_>>();
+    let mut all = collections::HashSet::new();
+    let mut queue = direct.iter().copied().collect::<Vec<_>>();
+    while let Some(id) = queue.pop() {
+        if all.insert(id) {
+            queue.extend(normal_deps(id));
+        }
+    }
+    let list = |ids: Vec<&cargo_metadata::PackageId>| {
+        let mut res = ids
+            .into_iter()
+            .filter_map(|id| packages.get(id))
+            .map(|p| (p.name.to_string(), p.version.to_string()))
+            .collect::<collections::HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        res.sort_unstable();
+        res
+    };
+
+    write_effective_dependencies(
+        w,
+        &list(all.iter().copied().collect()),
+        with_versions,
+        max_dependencies,
+        "An array of dependencies as resolved for the target and the enabled features.",
+        "The resolved dependencies as a comma-separated string.",
+    )?;
+    let mut sources = all
+        .iter()
+        .filter_map(|id| packages.get(id))
+        .map(|p| {
+            let kind = match &p.source {
+                None => "path",
+                Some(source) if source.repr.starts_with("git+") => "git",
+                Some(_) => "registry",
+            };
+            (p.name.to_string(), p.version.to_string(), kind)
+        })
+        .collect::<collections::HashSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    sources.sort_unstable();
+    write_dependency_sources(w, &sources)?;
+    #[cfg(feature = "dependency-tree")]
+    {
+        write_dependency_list(
+            w,
+            "DIRECT_DEPENDENCIES",
+            &list(direct.iter().copied().collect()),
+            with_versions,
+            "An array of direct dependencies as resolved for the target and the enabled features.",
+            "The direct dependencies as a comma-separated string.",
+        )?;
+        write_dependency_list(
+            w,
+            "INDIRECT_DEPENDENCIES",
+            &list(all.difference(&direct).copied().collect()),
+            with_versions,
+            "An array of indirect dependencies as resolved for the target and the enabled \
+            features.",
+            "The indirect dependencies as a comma-separated string.",
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes `deps` as an array `name` of `(name, version)`-tuples and as a comma-separated
+/// string `{name}_STR`; only their names if `with_versions` is `false`.
+fn write_dependency_list(
+    w: &mut BuiltWriter<'_>,
+    name: &str,
+    deps: &[(String, String)],
+    with_versions: bool,
+    doc: &str,
+    str_doc: &str,
+) -> io::Result<()> {
+    let str_name = format!("{name}_STR");
+    if with_versions {
+        write_variable!(
+            w,
+            name,
+            format_args!("[(&str, &str); {}]", deps.len()),
+            TupleArrayDisplay(deps),
+            doc
+        );
+        write_str_variable!(
+            w,
+            &str_name,
+            deps.iter()
+                .map(|(n, v)| format!("{n} {v}"))
+                .collect::<Vec<_>>()
+                .join(", "),
+            str_doc
+        );
+    } else {
+        let mut names = deps.iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>();
+        names.dedup();
+        write_variable!(
+            w,
+            name,
+            format_args!("[&str; {}]", names.len()),
+            ArrayDisplay(&names, |n, f| write!(f, "\"{}\"", n.escape_default())),
+            doc
+        );
+        write_str_variable!(w, &str_name, names.join(", "), str_doc);
+    }
+    Ok(())
+}
+
+/// Writes `deps` as `DEPENDENCIES`, keeping only the first `max_dependencies`, and whether
+/// the list was cut as `DEPENDENCIES_TRUNCATED`.
+fn write_effective_dependencies(
+    w: &mut BuiltWriter<'_>,
+    deps: &[(String, String)],
+    with_versions: bool,
+    max_dependencies: Option<usize>,
+    doc: &str,
+    str_doc: &str,
+) -> io::Result<()> {
+    let len = max_dependencies.map_or(deps.len(), |max| deps.len().min(max));
+    write_dependency_list(w, "DEPENDENCIES", &deps[..len], with_versions, doc, str_doc)?;
+    write_variable!(
+        w,
+        "DEPENDENCIES_TRUNCATED",
+        "bool",
+        len < deps.len(),
+        "Whether `DEPENDENCIES` was cut short via `Options::set_max_dependencies()`."
+    );
+    Ok(())
+}
+
 #[cfg(feature = "dependency-tree")]
 struct Dependencies {
     deps: Vec<(String, String)>,
+    sources: Vec<(String, String, &'static str)>,
     direct_deps: Vec<(String, String)>,
     indirect_deps: Vec<(String, String)>,
 }
@@ -45,13 +338,13 @@ impl Dependencies {
         let root_pkg_idx = graph
             .externals(EdgeDirection::Incoming)
             .collect::<collections::HashSet<_>>();
-        let deps = package_names(graph.node_indices().filter_map(|idx| {
-            if root_pkg_idx.contains(&idx) {
-                None
-            } else {
-                Some(&graph[idx])
-            }
-        }));
+        let packages = graph
+            .node_indices()
+            .filter(|idx| !root_pkg_idx.contains(idx))
+            .map(|idx| &graph[idx])
+            .collect::<Vec<_>>();
+        let deps = package_names(packages.iter().copied());
+        let sources = package_sources(packages);
         let direct_deps_idx = root_pkg_idx
             .iter()
             .flat_map(|idx| graph.neighbors_directed(*idx, EdgeDirection::Outgoing))
@@ -67,6 +360,7 @@ impl Dependencies {
 
         Self {
             deps,
+            sources,
             direct_deps,
             indirect_deps,
         }
@@ -74,103 +368,75 @@ impl Dependencies {
 }
 
 #[cfg(feature = "dependency-tree")]
-pub fn write_dependencies(manifest_location: &path::Path, mut w: &fs::File) -> io::Result<()> {
-    use io::{Read, Write};
+pub fn write_dependencies(
+    manifest_location: &path::Path,
+    with_versions: bool,
+    max_dependencies: Option<usize>,
+    w: &mut BuiltWriter<'_>,
+) -> io::Result<()> {
+    use io::Read;
 
     let mut lock_buf = String::new();
-    fs::File::open(find_lockfile(manifest_location)?)?.read_to_string(&mut lock_buf)?;
+    let lockfile_path = find_lockfile(manifest_location)?;
+    fs::File::open(&lockfile_path)?.read_to_string(&mut lock_buf)?;
+    w.rerun_if_changed(lockfile_path);
     let lockfile = lock_buf.parse().expect("Failed to parse lockfile");
 
     let dependencies = Dependencies::new(&lockfile);
 
-    write_variable!(
+    write_effective_dependencies(
         w,
-        "DEPENDENCIES",
-        format_args!("[(&str, &str); {}]", dependencies.deps.len()),
-        TupleArrayDisplay(&dependencies.deps),
-        "An array of effective dependencies as documented by `Cargo.lock`."
-    );
-    write_str_variable!(
-        w,
-        "DEPENDENCIES_STR",
-        dependencies
-            .deps
-            .iter()
-            .map(|(n, v)| format!("{n} {v}"))
-            .collect::<Vec<_>>()
-            .join(", "),
-        "The effective dependencies as a comma-separated string."
-    );
-
-    write_variable!(
+        &dependencies.deps,
+        with_versions,
+        max_dependencies,
+        "An array of effective dependencies as documented by `Cargo.lock`.",
+        "The effective dependencies as a comma-separated string.",
+    )?;
+    write_dependency_sources(w, &dependencies.sources)?;
+    write_dependency_list(
         w,
         "DIRECT_DEPENDENCIES",
-        format_args!("[(&str, &str); {}]", dependencies.direct_deps.len()),
-        TupleArrayDisplay(&dependencies.direct_deps),
-        "An array of direct dependencies as documented by `Cargo.lock`."
-    );
-    write_str_variable!(
-        w,
-        "DIRECT_DEPENDENCIES_STR",
-        dependencies
-            .direct_deps
-            .iter()
-            .map(|(n, v)| format!("{n} {v}"))
-            .collect::<Vec<_>>()
-            .join(", "),
-        "The direct dependencies as a comma-separated string."
-    );
-
-    write_variable!(
+        &dependencies.direct_deps,
+        with_versions,
+        "An array of direct dependencies as documented by `Cargo.lock`.",
+        "The direct dependencies as a comma-separated string.",
+    )?;
+    write_dependency_list(
         w,
         "INDIRECT_DEPENDENCIES",
-        format_args!("[(&str, &str); {}]", dependencies.indirect_deps.len()),
-        TupleArrayDisplay(&dependencies.indirect_deps),
-        "An array of indirect dependencies as documented by `Cargo.lock`."
-    );
-    write_str_variable!(
-        w,
-        "INDIRECT_DEPENDENCIES_STR",
-        dependencies
-            .indirect_deps
-            .iter()
-            .map(|(n, v)| format!("{n} {v}"))
-            .collect::<Vec<_>>()
-            .join(", "),
-        "The indirect dependencies as a comma-separated string."
-    );
-
-    Ok(())
+        &dependencies.indirect_deps,
+        with_versions,
+        "An array of indirect dependencies as documented by `Cargo.lock`.",
+        "The indirect dependencies as a comma-separated string.",
+    )
 }
 
 #[cfg(not(feature = "dependency-tree"))]
-pub fn write_dependencies(manifest_location: &path::Path, mut w: &fs::File) -> io::Result<()> {
-    use io::{Read, Write};
+pub fn write_dependencies(
+    manifest_location: &path::Path,
+    with_versions: bool,
+    max_dependencies: Option<usize>,
+    w: &mut BuiltWriter<'_>,
+) -> io::Result<()> {
+    use io::Read;
 
     let mut lock_buf = String::new();
-    fs::File::open(find_lockfile(manifest_location)?)?.read_to_string(&mut lock_buf)?;
+    let lockfile_path = find_lockfile(manifest_location)?;
+    fs::File::open(&lockfile_path)?.read_to_string(&mut lock_buf)?;
+    w.rerun_if_changed(lockfile_path);
     let lockfile: cargo_lock::Lockfile = lock_buf.parse().expect("Failed to parse lockfile");
 
     let deps = package_names(&lockfile.packages);
 
-    write_variable!(
-        w,
-        "DEPENDENCIES",
-        format_args!("[(&str, &str); {}]", deps.len()),
-        TupleArrayDisplay(&deps),
-        "An array of effective dependencies as documented by `Cargo.lock`."
-    );
-    write_str_variable!(
+    write_effective_dependencies(
         w,
-        "DEPENDENCIES_STR",
-        deps.iter()
-            .map(|(n, v)| format!("{n} {v}"))
-            .collect::<Vec<_>>()
-            .join(", "),
-        "The effective dependencies as a comma-separated string."
-    );
-
-    Ok(())
+        &deps,
+        with_versions,
+        max_dependencies,
+        "An array of effective dependencies as documented by `Cargo.lock`.",
+        "The effective dependencies as a comma-separated string.",
+    )?;
+    write_dependency_sources(w, &package_sources(&lockfile.packages))
 }
 
 #[cfg(test)]
@@ -242,6 +508,31 @@ dependencies = [
         );
     }
 
+    #[test]
+    fn dependency_sources() {
+        let lockfile: cargo_lock::Lockfile = format!(
+            r#"{LOCK_TOML_BUFFER}
+[[package]]
+name = "zed"
+version = "0.1.0"
+source = "git+https://github.com/example/zed?branch=main#0123456789abcdef0123456789abcdef01234567"
+"#
+        )
+        .parse()
+        .expect("Failed to parse lockfile");
+        let sources = super::package_sources(&lockfile.packages);
+        assert_eq!(sources[0], ("dummy".to_owned(), "0.1.0".to_owned(), "path"));
+        assert_eq!(
+            sources[1],
+            ("foo".to_owned(), "0.0.0".to_owned(), "registry")
+        );
+        assert_eq!(
+            sources.last().unwrap(),
+            &("zed".to_owned(), "0.1.0".to_owned(), "git")
+        );
+        assert_eq!(sources.len(), 7);
+    }
+
     #[test]
     #[cfg(feature = "dependency-tree")]
     fn direct_deps() {
@@ -273,4 +564,97 @@ dependencies = [
             ]
         );
     }
+
+    #[test]
+    fn check_locked() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(
+            root.path().join("Cargo.toml"),
+            "[package]\nname = \"dummy\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[workspace]\n",
+        )
+        .unwrap();
+        std::fs::create_dir(root.path().join("src")).unwrap();
+        std::fs::write(root.path().join("src").join("lib.rs"), "").unwrap();
+        std::fs::write(
+            root.path().join("Cargo.lock"),
+            "version = 3\n\n[[package]]\nname = \"dummy\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        let options = crate::Options::default();
+        let cargo = options.cargo(&crate::environment::EnvironmentMap::new());
+        let w = crate::BuiltWriter::new(&options);
+        super::check_locked(root.path(), &cargo, &w).unwrap();
+
+        std::fs::write(
+            root.path().join("Cargo.lock"),
+            "version = 3\n\n[[package]]\nname = \"dummy\"\nversion = \"0.0.1\"\n",
+        )
+        .unwrap();
+        assert!(super::check_locked(root.path(), &cargo, &w)
+            .unwrap_err()
+            .to_string()
+            .starts_with("Cargo.lock is out of date"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn check_locked_with_cargo_path() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = tempfile::tempdir().unwrap();
+        let shim = root.path().join("cargo-shim");
+        let marker = root.path().join("shim-was-run");
+        std::fs::write(&shim, format!("#!/bin/sh\ntouch '{}'\n", marker.display())).unwrap();
+        std::fs::set_permissions(&shim, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut options = crate::Options::default();
+        options.set_cargo_path(&shim);
+        let cargo = options.cargo(&crate::environment::EnvironmentMap::new());
+        assert_eq!(cargo, shim);
+        super::check_locked(root.path(), &cargo, &crate::BuiltWriter::new(&options)).unwrap();
+        assert!(marker.exists());
+    }
+
+    #[test]
+    fn dependency_names_only() {
+        let options = crate::Options::default();
+        let mut w = crate::BuiltWriter::new(&options);
+        let deps = [
+            ("foo".to_owned(), "0.0.0".to_owned()),
+            ("foo".to_owned(), "1.0.0".to_owned()),
+            ("nom".to_owned(), "7.1.3".to_owned()),
+        ];
+        super::write_dependency_list(&mut w, "DEPENDENCIES", &deps, false, "", "").unwrap();
+        let mut rust = Vec::new();
+        w.write_rust(&mut rust).unwrap();
+        let rust = String::from_utf8(rust).unwrap();
+        assert!(rust.contains(r#"pub static DEPENDENCIES: [&str; 2] = ["foo", "nom"];"#));
+        assert!(rust.contains(r#"pub static DEPENDENCIES_STR: &str = "foo, nom";"#));
+    }
+
+    #[test]
+    fn max_dependencies() {
+        let deps = [
+            ("foo".to_owned(), "0.0.0".to_owned()),
+            ("foobar".to_owned(), "0.0.0".to_owned()),
+            ("nom".to_owned(), "7.1.3".to_owned()),
+        ];
+        for (max, expected) in [
+            (Some(2), "[(&str, &str); 2]"),
+            (Some(3), "[(&str, &str); 3]"),
+            (None, "[(&str, &str); 3]"),
+        ] {
+            let options = crate::Options::default();
+            let mut w = crate::BuiltWriter::new(&options);
+            super::write_effective_dependencies(&mut w, &deps, true, max, "", "").unwrap();
+            let mut rust = Vec::new();
+            w.write_rust(&mut rust).unwrap();
+            let rust = String::from_utf8(rust).unwrap();
+            assert!(rust.contains(&format!("pub static DEPENDENCIES: {expected} = ")));
+            assert!(rust.contains(&format!(
+                "pub static DEPENDENCIES_TRUNCATED: bool = {};",
+                max == Some(2)
+            )));
+        }
+    }
 }
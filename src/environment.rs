@@ -1,6 +1,6 @@
-use crate::util::{self, ArrayDisplay};
-use crate::{fmt_option_str, write_str_variable, write_variable};
-use std::{cell, collections, env, ffi, fmt, fs, io, process};
+use crate::util::{self, ArrayDisplay, TupleArrayDisplay};
+use crate::{fmt_option, fmt_option_str, write_str_variable, write_variable, BuiltWriter};
+use std::{cell, collections, env, ffi, fmt, io, path, process};
 
 const BUILT_OVERRIDE_PREFIX: &str = "BUILT_OVERRIDE_";
 
@@ -37,6 +37,35 @@ pub struct EnvironmentMap {
     override_prefix: String,
 }
 
+/// Guesses the source of a package from the location of its manifest.
+///
+/// Packages that cargo downloaded live below `registry/src/<index>/` or
+/// `git/checkouts/<repository>/` in cargo's home-directory. Everything else is
+/// considered a path/local package, for which `None` is returned.
+fn detect_pkg_source(manifest_dir: &path::Path) -> Option<String> {
+    let components = manifest_dir
+        .components()
+        .map(|c| c.as_os_str().to_str())
+        .collect::<Vec<_>>();
+    components.windows(4).rev().find_map(|w| {
+        let name = |dir: &str| {
+            dir.rsplit_once('-')
+                .map_or(dir, |(name, _hash)| name)
+                .to_owned()
+        };
+        match (w[0]?, w[1]?, w[2]?) {
+            ("registry", "src", index) => Some(match name(index).as_str() {
+                "index.crates.io" | "github.com" => {
+                    "registry+https://github.com/rust-lang/crates.io-index".to_owned()
+                }
+                other => format!("registry+{other}"),
+            }),
+            ("git", "checkouts", repository) => Some(format!("git+{}", name(repository))),
+            _ => None,
+        }
+    })
+}
+
 fn get_version_from_cmd(executable: &ffi::OsStr) -> io::Result<String> {
     let output = process::Command::new(executable).arg("-V").output()?;
     let mut v = String::from_utf8(output.stdout).unwrap();
@@ -44,6 +73,154 @@ fn get_version_from_cmd(executable: &ffi::OsStr) -> io::Result<String> {
     Ok(v)
 }
 
+/// Parses the major and minor version from the output of `cargo -V`, e.g.
+/// `cargo 1.80.0 (376290515 2024-07-16)` or `cargo 1.82.0-nightly (ba8b39413 2024-08-16)`.
+fn parse_cargo_version(version: &str) -> Option<(u64, u64)> {
+    let mut parts = version
+        .strip_prefix("cargo ")?
+        .split(|c: char| !c.is_ascii_digit())
+        .take(2);
+    Some((parts.next()?.parse().ok()?, parts.next()?.parse().ok()?))
+}
+
+/// Parses a `rust-version` like `1.70` or `1.70.1` into its major, minor and patch version;
+/// missing components default to `0`.
+fn parse_rust_version(version: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().map_or(Some(0), |p| p.parse().ok())?;
+    let patch = parts.next().map_or(Some(0), |p| p.parse().ok())?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((major, minor, patch))
+}
+
+/// Parses the commit-date and the release-date of the compiler from the output of
+/// `rustc -vV`. Custom builds report the commit-date as `unknown`.
+///
+/// A nightly is released the day after its commit-date, as in rustup's `nightly-2024-08-17`;
+/// other channels are assumed to be released on their commit-date.
+fn parse_rustc_dates(verbose_version: &str) -> Option<(String, String)> {
+    let field = |key: &str| {
+        verbose_version
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix(':').map(str::trim))
+    };
+    let commit_date = field("commit-date")?;
+    let mut parts = commit_date.splitn(3, '-').map(|p| p.parse::<u32>().ok());
+    let (year, month, day) = (parts.next()??, parts.next()??, parts.next()??);
+    let days_in_month = match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => return None,
+    };
+    if day == 0 || day > days_in_month {
+        return None;
+    }
+    let release_date = if field("release").is_some_and(|r| r.ends_with("-nightly")) {
+        let (year, month, day) = match (day == days_in_month, month == 12) {
+            (false, _) => (year, month, day + 1),
+            (true, false) => (year, month + 1, 1),
+            (true, true) => (year + 1, 1, 1),
+        };
+        format!("{year:04}-{month:02}-{day:02}")
+    } else {
+        commit_date.to_owned()
+    };
+    Some((commit_date.to_owned(), release_date))
+}
+
+/// Finds the toolchain-file that rustup would use for `dir`, i.e. the nearest `rust-toolchain`
+/// or `rust-toolchain.toml` at or above it; the former takes precedence, like in rustup.
+fn find_rust_toolchain_file(dir: &path::Path) -> Option<path::PathBuf> {
+    dir.ancestors()
+        .flat_map(|dir| ["rust-toolchain", "rust-toolchain.toml"].map(|name| dir.join(name)))
+        .find(|path| path.is_file())
+}
+
+/// Extracts the channel from the content of a toolchain-file, i.e. the `channel` of its
+/// `[toolchain]`-table, or the first line of the legacy, non-TOML format.
+fn parse_rust_toolchain_channel(content: &str) -> Option<String> {
+    let mut is_toml = false;
+    let mut in_toolchain = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            is_toml = true;
+            in_toolchain = line == "[toolchain]";
+        } else if let Some(value) = in_toolchain
+            .then(|| line.strip_prefix("channel"))
+            .flatten()
+            .and_then(|value| value.trim_start().strip_prefix('='))
+        {
+            let value = value.trim_start();
+            let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+            let (channel, _) = value[1..].split_once(quote)?;
+            return (!channel.is_empty()).then(|| channel.to_owned());
+        }
+    }
+    if is_toml {
+        return None;
+    }
+    content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(ToOwned::to_owned)
+}
+
+/// Extracts a human-readable name of the operating system from the content of
+/// `/etc/os-release`, preferring `PRETTY_NAME` over `NAME` and `VERSION_ID`.
+fn parse_os_release(content: &str) -> Option<String> {
+    let get = |key: &str| {
+        content.lines().find_map(|line| {
+            let value = line.trim().strip_prefix(key)?.strip_prefix('=')?;
+            let value = value.trim_matches(|c| c == '"' || c == '\'');
+            (!value.is_empty()).then_some(value)
+        })
+    };
+    match (get("PRETTY_NAME"), get("NAME"), get("VERSION_ID")) {
+        (Some(pretty), _, _) => Some(pretty.to_owned()),
+        (None, Some(name), Some(version)) => Some(format!("{name} {version}")),
+        (None, name, version) => name.or(version).map(ToOwned::to_owned),
+    }
+}
+
+/// Queries the name and version of the operating system `built` is running on, on a
+/// best-effort basis.
+fn detect_os_version() -> Option<String> {
+    let from_cmd = |cmd: &str, args: &[&str]| {
+        let output = process::Command::new(cmd).args(args).output().ok()?;
+        let v = String::from_utf8(output.stdout).ok()?;
+        let v = v.trim();
+        (output.status.success() && !v.is_empty()).then(|| v.to_owned())
+    };
+    match env::consts::OS {
+        "macos" => from_cmd("sw_vers", &["-productVersion"]).map(|v| format!("macOS {v}")),
+        "windows" => from_cmd("cmd", &["/C", "ver"]),
+        _ => std::fs::read_to_string("/etc/os-release")
+            .or_else(|_| std::fs::read_to_string("/usr/lib/os-release"))
+            .ok()
+            .and_then(|content| parse_os_release(&content))
+            .or_else(|| from_cmd("uname", &["-sr"])),
+    }
+}
+
+/// Parses the comma-separated value of `CARGO_CFG_TARGET_HAS_ATOMIC`, e.g. `16,32,8,ptr`,
+/// ordered by width with `ptr` last.
+fn parse_target_has_atomic(value: &str) -> Vec<String> {
+    let mut widths = value
+        .split(',')
+        .map(str::trim)
+        .filter(|w| !w.is_empty())
+        .map(ToOwned::to_owned)
+        .collect::<Vec<_>>();
+    widths.sort_by_key(|w| w.parse::<u32>().unwrap_or(u32::MAX));
+    widths
+}
+
 impl EnvironmentMap {
     pub fn new() -> Self {
         let map = env::vars_os()
@@ -128,9 +305,7 @@ impl EnvironmentMap {
         })
     }
 
-    pub fn write_ci(&self, mut w: &fs::File) -> io::Result<()> {
-        use io::Write;
-
+    pub fn write_ci(&self, w: &mut BuiltWriter<'_>) -> io::Result<()> {
         let ci = match self.get_override_var("CI_PLATFORM") {
             Some(v) => v,
             None => self.detect_ci().map(|ci| ci.to_string()),
@@ -145,8 +320,12 @@ impl EnvironmentMap {
         Ok(())
     }
 
-    pub fn write_env(&self, mut w: &fs::File) -> io::Result<()> {
-        use io::Write;
+    pub fn write_env(&self, w: &mut BuiltWriter<'_>) -> io::Result<()> {
+        // The `PKG_*`-variables are derived from the manifest.
+        if let Some(manifest_dir) = self.get("CARGO_MANIFEST_DIR") {
+            w.rerun_if_changed(path::Path::new(manifest_dir).join("Cargo.toml"));
+        }
+
         macro_rules! write_env_str {
             ($(($name:ident, $env_name:expr, $doc:expr)),*) => {$(
                 let v = match self.get_override_var(stringify!($name)) {
@@ -217,6 +396,73 @@ impl EnvironmentMap {
             )
         );
 
+        write_variable!(
+            w,
+            "IS_CROSS_COMPILE",
+            "bool",
+            self.get_override_var("IS_CROSS_COMPILE")
+                .unwrap_or_else(|| {
+                    let triple = |name| {
+                        self.get_override_var::<&str>(name)
+                            .or_else(|| self.get(name))
+                    };
+                    triple("HOST") != triple("TARGET")
+                }),
+            "`true` if `TARGET` differs from `HOST`, i.e. if the crate was cross-compiled."
+        );
+
+        write_variable!(
+            w,
+            "PKG_SOURCE",
+            "Option<&str>",
+            fmt_option_str(
+                self.get_override_var("PKG_SOURCE")
+                    .unwrap_or_else(|| {
+                        detect_pkg_source(self.get("CARGO_MANIFEST_DIR")?.as_ref())
+                    })
+                    .map(|v| v.escape_default().to_string())
+            ),
+            "The source the package was downloaded from by cargo, e.g. \
+            `registry+https://github.com/rust-lang/crates.io-index`; `None` for path/local packages."
+        );
+
+        let rust_version = self
+            .get_override_var("PKG_RUST_VERSION")
+            .unwrap_or_else(|| {
+                self.get("CARGO_PKG_RUST_VERSION")
+                    .filter(|v| !v.is_empty())
+                    .map(|v| v.to_owned())
+            });
+        let rust_version_parts = rust_version.as_deref().and_then(parse_rust_version);
+        write_variable!(
+            w,
+            "PKG_RUST_VERSION",
+            "Option<&str>",
+            fmt_option_str(rust_version),
+            "The minimum supported Rust version, as given by `rust-version` in Cargo.toml."
+        );
+        write_variable!(
+            w,
+            "PKG_RUST_VERSION_MAJOR",
+            "Option<u64>",
+            fmt_option(rust_version_parts.map(|(major, _, _)| major)),
+            "The major version of `PKG_RUST_VERSION`."
+        );
+        write_variable!(
+            w,
+            "PKG_RUST_VERSION_MINOR",
+            "Option<u64>",
+            fmt_option(rust_version_parts.map(|(_, minor, _)| minor)),
+            "The minor version of `PKG_RUST_VERSION`; `0` if not given."
+        );
+        write_variable!(
+            w,
+            "PKG_RUST_VERSION_PATCH",
+            "Option<u64>",
+            fmt_option(rust_version_parts.map(|(_, _, patch)| patch)),
+            "The patch version of `PKG_RUST_VERSION`; `0` if not given."
+        );
+
         write_str_variable!(
             w,
             "OPT_LEVEL",
@@ -247,12 +493,26 @@ impl EnvironmentMap {
                 .unwrap_or_else(|| env::var("DEBUG").unwrap() == "true"),
             "Value of DEBUG for the profile used during compilation."
         );
+
+        write_variable!(
+            w,
+            "INCREMENTAL",
+            "Option<bool>",
+            fmt_option(match self.get_override_var("INCREMENTAL") {
+                Some(v) => v,
+                None => match self.get("CARGO_INCREMENTAL") {
+                    Some("1") => Some(true),
+                    Some("0") => Some(false),
+                    _ => None,
+                },
+            }),
+            "Whether incremental compilation was forced on or off via `CARGO_INCREMENTAL`; \
+            `None` if it was left to the profile."
+        );
         Ok(())
     }
 
-    pub fn write_features(&self, mut w: &fs::File) -> io::Result<()> {
-        use io::Write;
-
+    pub fn write_features(&self, w: &mut BuiltWriter<'_>) -> io::Result<()> {
         let mut features: Vec<String> = self
             .get_override_var("FEATURES")
             .unwrap_or_else(|| {
@@ -311,12 +571,23 @@ impl EnvironmentMap {
             "The feature-string as above, from lowercase strings."
         );
 
+        let banner = lowercase_features
+            .iter()
+            .map(|name| name.replace('_', "-"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        write_str_variable!(
+            w,
+            "FEATURES_BANNER",
+            banner,
+            "The lowercase features as a space-separated string, with underscores written as \
+            hyphens like in `Cargo.toml`, e.g. for a version-banner."
+        );
+
         Ok(())
     }
 
-    pub fn write_cfg(&self, mut w: &fs::File) -> io::Result<()> {
-        use io::Write;
-
+    pub fn write_cfg(&self, w: &mut BuiltWriter<'_>) -> io::Result<()> {
         write_str_variable!(
             w,
             "CFG_TARGET_ARCH",
@@ -365,12 +636,62 @@ impl EnvironmentMap {
             "The pointer width, given by `CARGO_CFG_TARGET_POINTER_WIDTH`."
         );
 
+        write_variable!(
+            w,
+            "DEBUG_ASSERTIONS",
+            "bool",
+            self.get_override_var("DEBUG_ASSERTIONS")
+                .unwrap_or_else(|| self.contains_key("CARGO_CFG_DEBUG_ASSERTIONS")),
+            "Whether `debug_assertions` were enabled, given by `CARGO_CFG_DEBUG_ASSERTIONS`."
+        );
+
+        write_str_variable!(
+            w,
+            "CFG_PANIC",
+            self.get_override_var("CFG_PANIC")
+                .unwrap_or_else(|| self.get("CARGO_CFG_PANIC").unwrap_or("unwind")),
+            "The panic-strategy, i.e. `unwind` or `abort`, given by `CARGO_CFG_PANIC`."
+        );
+
+        let has_atomic = self
+            .get_override_var("CFG_TARGET_HAS_ATOMIC")
+            .unwrap_or_else(|| {
+                parse_target_has_atomic(self.get("CARGO_CFG_TARGET_HAS_ATOMIC").unwrap_or_default())
+            });
+        write_variable!(
+            w,
+            "CFG_TARGET_HAS_ATOMIC",
+            format_args!("[&str; {}]", has_atomic.len()),
+            ArrayDisplay(&has_atomic, |t, f| write!(f, "\"{}\"", t.escape_default())),
+            "The widths of the atomic types supported by the target, given by \
+            `CARGO_CFG_TARGET_HAS_ATOMIC`, e.g. `8` or `ptr`."
+        );
+
+        let mut cfgs = self
+            .filter_map_keys(|k| k.starts_with("CARGO_CFG_").then_some(k))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|k| {
+                (
+                    k["CARGO_CFG_".len()..].to_lowercase(),
+                    self.get(k).unwrap_or_default().to_owned(),
+                )
+            })
+            .collect::<Vec<_>>();
+        cfgs.sort_unstable();
+        write_variable!(
+            w,
+            "CFG_ALL",
+            format_args!("[(&str, &str); {}]", cfgs.len()),
+            TupleArrayDisplay(&cfgs),
+            "Every `CARGO_CFG_*`-variable set by cargo, as lowercase name without the prefix \
+            and value; the value is empty for cfgs without a value, e.g. `unix`."
+        );
+
         Ok(())
     }
 
-    pub fn write_compiler_version(&self, mut w: &fs::File) -> io::Result<()> {
-        use std::io::Write;
-
+    pub fn write_compiler_version(&self, w: &mut BuiltWriter<'_>) -> io::Result<()> {
         let rustc;
         let rustc_version;
         match self.get_override_var("RUSTC") {
@@ -399,6 +720,36 @@ impl EnvironmentMap {
             }
         }
 
+        let cargo_version = match self.get_override_var("CARGO_VERSION") {
+            Some(v) => Some(v),
+            None => self
+                .get("CARGO")
+                .and_then(|cargo| get_version_from_cmd(cargo.as_ref()).ok())
+                .filter(|v| !v.is_empty()),
+        };
+        let cargo_major_minor = cargo_version.as_deref().and_then(parse_cargo_version);
+
+        let mut rustc_commit_date: Option<Option<String>> =
+            self.get_override_var("RUSTC_COMMIT_DATE");
+        let mut rustc_release_date: Option<Option<String>> =
+            self.get_override_var("RUSTC_RELEASE_DATE");
+        if (rustc_commit_date.is_none() && w.emits("RUSTC_COMMIT_DATE"))
+            || (rustc_release_date.is_none() && w.emits("RUSTC_RELEASE_DATE"))
+        {
+            let dates = match self.get_override_var::<&str>("RUSTC") {
+                Some(_) => None,
+                None => process::Command::new(rustc)
+                    .arg("-vV")
+                    .output()
+                    .ok()
+                    .and_then(|output| String::from_utf8(output.stdout).ok())
+                    .and_then(|v| parse_rustc_dates(&v)),
+            };
+            let (commit_date, release_date) = dates.unzip();
+            rustc_commit_date.get_or_insert(commit_date);
+            rustc_release_date.get_or_insert(release_date);
+        }
+
         write_str_variable!(
             w,
             "RUSTC_VERSION",
@@ -414,9 +765,160 @@ impl EnvironmentMap {
                 "The output of `{rustdoc} -V`; empty string if `{rustdoc} -V` failed to execute"
             )
         );
+
+        write_variable!(
+            w,
+            "CARGO_VERSION",
+            "Option<&str>",
+            fmt_option_str(cargo_version),
+            "The output of `cargo -V`; `None` if `cargo -V` failed to execute."
+        );
+        write_variable!(
+            w,
+            "CARGO_VERSION_MAJOR",
+            "Option<u64>",
+            fmt_option(cargo_major_minor.map(|(major, _)| major)),
+            "The major version of cargo, as parsed from `CARGO_VERSION`."
+        );
+        write_variable!(
+            w,
+            "CARGO_VERSION_MINOR",
+            "Option<u64>",
+            fmt_option(cargo_major_minor.map(|(_, minor)| minor)),
+            "The minor version of cargo, as parsed from `CARGO_VERSION`."
+        );
+        write_variable!(
+            w,
+            "RUSTC_COMMIT_DATE",
+            "Option<&str>",
+            fmt_option_str(rustc_commit_date.flatten()),
+            "The commit-date of the compiler as reported by `rustc -vV`, e.g. `2024-08-16`; \
+            `None` if the toolchain does not report it, e.g. a custom build."
+        );
+        write_variable!(
+            w,
+            "RUSTC_RELEASE_DATE",
+            "Option<&str>",
+            fmt_option_str(rustc_release_date.flatten()),
+            "The release-date of the compiler, derived from `RUSTC_COMMIT_DATE`: The day after \
+            for a nightly, as in `nightly-2024-08-17`, the same day otherwise."
+        );
+
+        let toolchain_channel = match self.get_override_var("RUST_TOOLCHAIN_CHANNEL") {
+            Some(v) => v,
+            None => self
+                .get("CARGO_MANIFEST_DIR")
+                .and_then(|dir| find_rust_toolchain_file(dir.as_ref()))
+                .and_then(|path| {
+                    let content = std::fs::read_to_string(&path).ok();
+                    w.rerun_if_changed(path);
+                    parse_rust_toolchain_channel(&content?)
+                }),
+        };
+        write_variable!(
+            w,
+            "RUST_TOOLCHAIN_CHANNEL",
+            "Option<&str>",
+            fmt_option_str(toolchain_channel.map(|c| c.escape_default().to_string())),
+            "The toolchain pinned by the nearest `rust-toolchain.toml` or `rust-toolchain`-file \
+            at or above the manifest, e.g. `1.75.0` or `nightly-2024-01-01`; `None` if there is \
+            none. This is the toolchain the project intends to use, not necessarily the one \
+            given by `RUSTC_VERSION`."
+        );
+        Ok(())
+    }
+
+    pub fn write_build_os(&self, w: &mut BuiltWriter<'_>) -> io::Result<()> {
+        let os = self
+            .get_override_var("BUILD_OS")
+            .unwrap_or(env::consts::OS)
+            .to_owned();
+        write_str_variable!(
+            w,
+            "BUILD_OS",
+            os,
+            "The operating system of the machine that built the crate, e.g. `linux` or `macos`."
+        );
+
+        if w.emits("BUILD_OS_VERSION") {
+            let version = match self.get_override_var("BUILD_OS_VERSION") {
+                Some(v) => v,
+                None => detect_os_version(),
+            };
+            write_variable!(
+                w,
+                "BUILD_OS_VERSION",
+                "Option<&str>",
+                fmt_option_str(version.map(|v| v.escape_default().to_string())),
+                "The name and version of the operating system of the machine that built the crate; `None` if it could not be determined."
+            );
+        }
+
+        let arch = self
+            .get_override_var("BUILD_ARCH")
+            .unwrap_or(env::consts::ARCH)
+            .to_owned();
+        write_str_variable!(
+            w,
+            "BUILD_ARCH",
+            arch,
+            "The CPU architecture of the machine that built the crate, e.g. `x86_64` or `aarch64`."
+        );
+
+        if w.emits("BUILD_CPU_COUNT") {
+            write_variable!(
+                w,
+                "BUILD_CPU_COUNT",
+                "usize",
+                self.get_override_var("BUILD_CPU_COUNT").unwrap_or_else(|| {
+                    std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get)
+                }),
+                "The number of CPUs available to `built` on the machine that built the crate; \
+                `1` if it could not be determined. This differs between machines and is \
+                therefore not reproducible."
+            );
+        }
+        Ok(())
+    }
+
+    pub fn write_rustc_sysroot(&self, w: &mut BuiltWriter<'_>) -> io::Result<()> {
+        let sysroot = match self.get_override_var("RUSTC_SYSROOT") {
+            Some(v) => v,
+            None => self.get("RUSTC").and_then(|rustc| {
+                let output = process::Command::new(rustc)
+                    .args(["--print", "sysroot"])
+                    .output()
+                    .ok()?;
+                let sysroot = String::from_utf8(output.stdout).ok()?;
+                let sysroot = sysroot.trim();
+                (output.status.success() && !sysroot.is_empty()).then(|| sysroot.to_owned())
+            }),
+        };
+        write_variable!(
+            w,
+            "RUSTC_SYSROOT",
+            "Option<&str>",
+            fmt_option_str(sysroot.map(|v| v.escape_default().to_string())),
+            "The output of `rustc --print sysroot`, a path on the build-machine; `None` if `rustc` could not be run."
+        );
         Ok(())
     }
 
+    /// Detect whether the build-script is run by an IDE, i.e. `rust-analyzer`, to analyze
+    /// the code instead of building it.
+    ///
+    /// `rust-analyzer` is detected by the `RUST_ANALYZER_INTERNALS_DO_NOT_USE` and
+    /// `RA_RUSTC_WRAPPER`-variables it sets, or by a `RUSTC_WRAPPER` named `rust-analyzer`.
+    pub fn detect_ide(&self) -> bool {
+        self.contains_key("RUST_ANALYZER_INTERNALS_DO_NOT_USE")
+            || self.contains_key("RA_RUSTC_WRAPPER")
+            || self.get("RUSTC_WRAPPER").is_some_and(|wrapper| {
+                path::Path::new(wrapper)
+                    .file_stem()
+                    .is_some_and(|name| name == "rust-analyzer")
+            })
+    }
+
     pub fn detect_ci(&self) -> Option<CIPlatform> {
         macro_rules! detect {
             ($(($k:expr, $v:expr, $i:ident)),*) => {$(
@@ -537,3 +1039,168 @@ impl fmt::Display for CIPlatform {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_pkg_source() {
+        assert_eq!(
+            detect_pkg_source(path::Path::new(
+                "/home/foo/.cargo/registry/src/index.crates.io-1949cf8c6b5b557f/built-0.8.0"
+            )),
+            Some("registry+https://github.com/rust-lang/crates.io-index".to_owned())
+        );
+        assert_eq!(
+            detect_pkg_source(path::Path::new(
+                "/home/foo/.cargo/registry/src/my.registry.org-0123456789abcdef/foo-1.0.0"
+            )),
+            Some("registry+my.registry.org".to_owned())
+        );
+        assert_eq!(
+            detect_pkg_source(path::Path::new(
+                "/home/foo/.cargo/git/checkouts/built-0123456789abcdef/ea78f5e/example_project"
+            )),
+            Some("git+built".to_owned())
+        );
+        assert_eq!(
+            detect_pkg_source(path::Path::new("/home/foo/src/built")),
+            None
+        );
+        assert_eq!(
+            detect_pkg_source(path::Path::new("/home/foo/registry/src/built")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_cargo_version() {
+        assert_eq!(
+            parse_cargo_version("cargo 1.80.0 (376290515 2024-07-16)"),
+            Some((1, 80))
+        );
+        assert_eq!(
+            parse_cargo_version("cargo 1.82.0-nightly (ba8b39413 2024-08-16)"),
+            Some((1, 82))
+        );
+        assert_eq!(parse_cargo_version("cargo 1.43.0"), Some((1, 43)));
+        assert_eq!(parse_cargo_version("cargo 2"), None);
+        assert_eq!(parse_cargo_version("rustc 1.80.0"), None);
+        assert_eq!(parse_cargo_version(""), None);
+    }
+
+    #[test]
+    fn test_parse_rust_version() {
+        assert_eq!(parse_rust_version("1.70"), Some((1, 70, 0)));
+        assert_eq!(parse_rust_version("1.70.1"), Some((1, 70, 1)));
+        assert_eq!(parse_rust_version("1"), Some((1, 0, 0)));
+        assert_eq!(parse_rust_version("1.70.1.2"), None);
+        assert_eq!(parse_rust_version("1.x"), None);
+        assert_eq!(parse_rust_version(""), None);
+    }
+
+    #[test]
+    fn test_parse_rust_toolchain_channel() {
+        assert_eq!(
+            parse_rust_toolchain_channel(
+                "[toolchain]\nchannel = \"1.75.0\" # pinned\ncomponents = [\"rustfmt\"]\n"
+            ),
+            Some("1.75.0".to_owned())
+        );
+        assert_eq!(
+            parse_rust_toolchain_channel("[other]\nchannel = 'x'\n[toolchain]\nchannel='nightly'"),
+            Some("nightly".to_owned())
+        );
+        assert_eq!(
+            parse_rust_toolchain_channel("[toolchain]\ncomponents = [\"rustfmt\"]\n"),
+            None
+        );
+        assert_eq!(
+            parse_rust_toolchain_channel("\nnightly-2024-01-01\n"),
+            Some("nightly-2024-01-01".to_owned())
+        );
+        assert_eq!(parse_rust_toolchain_channel(""), None);
+    }
+
+    #[test]
+    fn test_find_rust_toolchain_file() {
+        let root = tempfile::tempdir().unwrap();
+        let sub = root.path().join("sub");
+        std::fs::create_dir(&sub).unwrap();
+        std::fs::write(root.path().join("rust-toolchain.toml"), "").unwrap();
+        assert_eq!(
+            find_rust_toolchain_file(&sub),
+            Some(root.path().join("rust-toolchain.toml"))
+        );
+        std::fs::write(root.path().join("rust-toolchain"), "").unwrap();
+        assert_eq!(
+            find_rust_toolchain_file(&sub),
+            Some(root.path().join("rust-toolchain"))
+        );
+    }
+
+    #[test]
+    fn test_parse_rustc_dates() {
+        let nightly = "rustc 1.82.0-nightly (2c93fabd9 2024-08-16)\nbinary: rustc\n\
+            commit-hash: 2c93fabd98d2c183bcb3afed1f7d51b2517ac5ed\n\
+            commit-date: 2024-08-16\nhost: x86_64-unknown-linux-gnu\n\
+            release: 1.82.0-nightly\nLLVM version: 19.1.0\n";
+        assert_eq!(
+            parse_rustc_dates(nightly),
+            Some(("2024-08-16".to_owned(), "2024-08-17".to_owned()))
+        );
+        assert_eq!(
+            parse_rustc_dates("commit-date: 2024-02-29\nrelease: 1.78.0-nightly\n"),
+            Some(("2024-02-29".to_owned(), "2024-03-01".to_owned()))
+        );
+        assert_eq!(
+            parse_rustc_dates("commit-date: 2023-12-31\nrelease: 1.76.0-nightly\n"),
+            Some(("2023-12-31".to_owned(), "2024-01-01".to_owned()))
+        );
+        assert_eq!(
+            parse_rustc_dates("commit-date: 2024-07-16\nrelease: 1.80.0\n"),
+            Some(("2024-07-16".to_owned(), "2024-07-16".to_owned()))
+        );
+        assert_eq!(
+            parse_rustc_dates("commit-hash: unknown\ncommit-date: unknown\n"),
+            None
+        );
+        assert_eq!(parse_rustc_dates("commit-date: 2023-02-29\n"), None);
+        assert_eq!(parse_rustc_dates(""), None);
+    }
+
+    #[test]
+    fn test_parse_target_has_atomic() {
+        assert_eq!(
+            parse_target_has_atomic("16,32,64,8,ptr"),
+            ["8", "16", "32", "64", "ptr"]
+        );
+        // e.g. `thumbv6m-none-eabi` lacks atomics wider than the pointer
+        assert_eq!(
+            parse_target_has_atomic("32,ptr,16,8"),
+            ["8", "16", "32", "ptr"]
+        );
+        assert!(parse_target_has_atomic("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_os_release() {
+        assert_eq!(
+            parse_os_release(
+                "NAME=\"Ubuntu\"\nVERSION_ID=\"24.04\"\nPRETTY_NAME=\"Ubuntu 24.04.1 LTS\"\n"
+            ),
+            Some("Ubuntu 24.04.1 LTS".to_owned())
+        );
+        assert_eq!(
+            parse_os_release("ID=alpine\nNAME='Alpine Linux'\nVERSION_ID=3.20.3\n"),
+            Some("Alpine Linux 3.20.3".to_owned())
+        );
+        assert_eq!(
+            parse_os_release("NAME=\"Arch Linux\"\nPRETTY_NAME=\"\"\n"),
+            Some("Arch Linux".to_owned())
+        );
+        assert_eq!(parse_os_release("ID=foo\n"), None);
+        assert_eq!(parse_os_release(""), None);
+    }
+}
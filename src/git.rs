@@ -8,30 +8,265 @@
 /// Errors from `git2` are returned if the repository does exists at all.
 #[cfg(feature = "git2")]
 pub fn get_repo_description(root: &std::path::Path) -> Result<Option<(String, bool)>, git2::Error> {
-    match git2::Repository::discover(root) {
-        Ok(repo) => {
-            let mut desc_opt = git2::DescribeOptions::new();
-            desc_opt.describe_tags().show_commit_oid_as_fallback(true);
-            let tag = repo
-                .describe(&desc_opt)
-                .and_then(|desc| desc.format(None))?;
-            let mut st_opt = git2::StatusOptions::new();
-            st_opt.include_ignored(false);
-            st_opt.include_untracked(false);
-            let dirty = repo
-                .statuses(Some(&mut st_opt))?
-                .iter()
-                .any(|status| !matches!(status.status(), git2::Status::CURRENT));
-            Ok(Some((tag, dirty)))
-        }
-        Err(ref e)
-            if e.class() == git2::ErrorClass::Repository
-                && e.code() == git2::ErrorCode::NotFound =>
-        {
-            Ok(None)
-        }
-        Err(e) => Err(e),
+    let Some(repo) = open_repo(root, false)? else {
+        return Ok(None);
+    };
+    get_repo_description_with(&repo, crate::DirtyStatuses::default(), false, &[])
+}
+
+/// Maps `statuses` to the `git2::Status`-flags which make the repository dirty.
+#[cfg(feature = "git2")]
+fn dirty_status_mask(statuses: crate::DirtyStatuses) -> git2::Status {
+    use crate::DirtyStatuses;
+    use git2::Status;
+
+    [
+        (
+            DirtyStatuses::INDEX,
+            Status::INDEX_NEW
+                | Status::INDEX_MODIFIED
+                | Status::INDEX_DELETED
+                | Status::INDEX_RENAMED
+                | Status::CONFLICTED,
+        ),
+        (
+            DirtyStatuses::WORKTREE,
+            Status::WT_MODIFIED
+                | Status::WT_DELETED
+                | Status::WT_RENAMED
+                | Status::WT_UNREADABLE
+                | Status::CONFLICTED,
+        ),
+        (
+            DirtyStatuses::TYPECHANGE,
+            Status::INDEX_TYPECHANGE | Status::WT_TYPECHANGE,
+        ),
+        (DirtyStatuses::UNTRACKED, Status::WT_NEW),
+        (DirtyStatuses::IGNORED, Status::IGNORED),
+    ]
+    .into_iter()
+    .filter(|(kind, _)| statuses.contains(*kind))
+    .fold(Status::empty(), |mask, (_, status)| mask | status)
+}
+
+/// Like `get_repo_description()`, but only the kinds of changes in `statuses` make the
+/// repository dirty, and HEAD is described against all references,
+/// like `git describe --all`, if `describe_all` is `true`. Only the files matching
+/// `pathspecs`, relative to the repository's root, are considered unless it is empty.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_description_with(
+    repo: &git2::Repository,
+    statuses: crate::DirtyStatuses,
+    describe_all: bool,
+    pathspecs: &[std::path::PathBuf],
+) -> Result<Option<(String, bool)>, git2::Error> {
+    let tag = repo
+        .describe(&describe_options(describe_all))
+        .and_then(|desc| desc.format(None))?;
+    let mut st_opt = git2::StatusOptions::new();
+    st_opt.include_ignored(statuses.contains(crate::DirtyStatuses::IGNORED));
+    st_opt.include_untracked(statuses.contains(crate::DirtyStatuses::UNTRACKED));
+    for pathspec in pathspecs {
+        st_opt.pathspec(pathspec.as_path());
+    }
+    let dirty = repo
+        .statuses(Some(&mut st_opt))?
+        .iter()
+        .any(|status| status.status().intersects(dirty_status_mask(statuses)));
+    Ok(Some((tag, dirty)))
+}
+
+/// Retrieves the number of inserted and deleted lines of the working tree and the index,
+/// compared to HEAD, like `git diff HEAD --shortstat`. Untracked files are not considered.
+///
+/// If there are no changes, `Ok(None)` is returned.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_dirty_lines(
+    repo: &git2::Repository,
+) -> Result<Option<(usize, usize)>, git2::Error> {
+    let head = repo.head()?.peel_to_tree()?;
+    let diff = repo.diff_tree_to_workdir_with_index(Some(&head), None)?;
+    if diff.deltas().len() == 0 {
+        return Ok(None);
+    }
+    let stats = diff.stats()?;
+    Ok(Some((stats.insertions(), stats.deletions())))
+}
+
+/// Determines whether the index has unmerged paths, i.e. unresolved merge-conflicts, like
+/// `git status` would report them as `Status::CONFLICTED`.
+///
+/// If the repository is bare, `Ok(None)` is returned.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_has_conflicts(repo: &git2::Repository) -> Result<Option<bool>, git2::Error> {
+    if repo.is_bare() {
+        return Ok(None);
     }
+    Ok(Some(repo.index()?.has_conflicts()))
+}
+
+/// Retrieves the paths of the files in the working tree and the index that differ from HEAD,
+/// relative to the repository's root and sorted by name. Untracked and ignored files are not
+/// considered.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_dirty_files(
+    repo: &git2::Repository,
+) -> Result<Option<Vec<String>>, git2::Error> {
+    let mut st_opt = git2::StatusOptions::new();
+    st_opt.include_ignored(false);
+    st_opt.include_untracked(false);
+    let mut files = repo
+        .statuses(Some(&mut st_opt))?
+        .iter()
+        .filter(|status| !matches!(status.status(), git2::Status::CURRENT))
+        .map(|status| String::from_utf8_lossy(status.path_bytes()).into_owned())
+        .collect::<Vec<_>>();
+    files.sort();
+    Ok(Some(files))
+}
+
+/// Retrieves the git-tag or hash describing `committish`, e.g. `origin/main`, instead of HEAD.
+/// All references are considered, like `git describe --all`, if `describe_all` is `true`.
+///
+/// If `committish` can't be resolved, `Ok(None)` is returned.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_describe(
+    repo: &git2::Repository,
+    committish: &str,
+    describe_all: bool,
+) -> Result<Option<String>, git2::Error> {
+    let Ok(object) = repo.revparse_single(committish) else {
+        return Ok(None);
+    };
+    let tag = object
+        .peel(git2::ObjectType::Commit)?
+        .describe(&describe_options(describe_all))
+        .and_then(|desc| desc.format(None))?;
+    Ok(Some(tag))
+}
+
+/// Like `git describe --tags`, or `git describe --all` if `describe_all` is `true`,
+/// falling back to the short commit id.
+#[cfg(feature = "git2")]
+fn describe_options(describe_all: bool) -> git2::DescribeOptions {
+    let mut desc_opt = git2::DescribeOptions::new();
+    if describe_all {
+        desc_opt.describe_all();
+    } else {
+        desc_opt.describe_tags();
+    }
+    desc_opt.show_commit_oid_as_fallback(true);
+    desc_opt
+}
+
+/// Resolves `committish`, e.g. `HEAD` or `v1.0.0`, to the id of the commit it refers to, which
+/// the commit-scoped `get_repo_*()`-functions then read from.
+///
+/// If `committish` can't be resolved, `Ok(None)` is returned.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_commit_id(
+    repo: &git2::Repository,
+    committish: &str,
+) -> Result<Option<git2::Oid>, git2::Error> {
+    let Ok(object) = repo.revparse_single(committish) else {
+        return Ok(None);
+    };
+    Ok(Some(object.peel_to_commit()?.id()))
+}
+
+/// Retrieves the long- and short-hash of `commit`.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_commit(
+    repo: &git2::Repository,
+    commit: git2::Oid,
+) -> Result<Option<(String, String)>, git2::Error> {
+    let commit = repo.find_object(commit, Some(git2::ObjectType::Commit))?;
+    let commit_short = commit.short_id()?;
+    Ok(Some((
+        commit.id().to_string(),
+        commit_short.as_str().unwrap_or_default().to_owned(),
+    )))
+}
+
+/// Retrieves the name of the most recent tag reachable from `commit`, like
+/// `git describe --tags --abbrev=0`.
+///
+/// If no tag is reachable, `Ok(None)` is returned.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_nearest_tag(
+    repo: &git2::Repository,
+    commit: git2::Oid,
+) -> Result<Option<String>, git2::Error> {
+    let mut desc_opt = git2::DescribeOptions::new();
+    desc_opt.describe_tags();
+    let commit = repo.find_commit(commit)?;
+    let desc = match commit.as_object().describe(&desc_opt) {
+        Err(e) if e.class() == git2::ErrorClass::Describe => return Ok(None),
+        desc => desc?,
+    };
+    let mut format_opt = git2::DescribeFormatOptions::new();
+    format_opt.abbreviated_size(0);
+    Ok(Some(desc.format(Some(&format_opt))?))
+}
+
+/// Retrieves the full hash of the commit the tag `tag` points to.
+///
+/// If there is no such tag, `Ok(None)` is returned.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_tag_commit(
+    repo: &git2::Repository,
+    tag: &str,
+) -> Result<Option<String>, git2::Error> {
+    let reference = match repo.find_reference(&format!("refs/tags/{tag}")) {
+        Ok(reference) => reference,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let commit = reference.peel_to_commit()?;
+    Ok(Some(commit.id().to_string()))
+}
+
+/// Retrieves the note attached to `commit` in the default notes-ref, i.e.
+/// `core.notesRef` or `refs/notes/commits`.
+///
+/// If there is no note, or if the note is not valid UTF-8, `Ok(None)` is returned.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_commit_notes(
+    repo: &git2::Repository,
+    commit: git2::Oid,
+) -> Result<Option<String>, git2::Error> {
+    let note = match repo.find_note(None, commit) {
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+        note => note?,
+    };
+    Ok(note.message().map(ToOwned::to_owned))
 }
 
 /// Retrieves the branch name and hash of HEAD.
@@ -48,29 +283,479 @@ pub fn get_repo_description(root: &std::path::Path) -> Result<Option<(String, bo
 pub fn get_repo_head(
     root: &std::path::Path,
 ) -> Result<Option<(Option<String>, String, String)>, git2::Error> {
-    match git2::Repository::discover(root) {
-        Ok(repo) => {
-            // Supposed to be the reference pointed to by HEAD, but it's HEAD
-            // itself, if detached
-            let head_ref = repo.head()?;
-            let branch = {
-                // Check whether `head` is really the pointed to reference and
-                // not HEAD itself.
-                if repo.head_detached()? {
-                    None
-                } else {
-                    head_ref.name()
-                }
-            };
-            let head = head_ref.peel_to_commit()?;
-            let commit = head.id();
-            let commit_short = head.into_object().short_id()?;
-            Ok(Some((
-                branch.map(ToString::to_string),
-                format!("{commit}"),
-                commit_short.as_str().unwrap_or_default().to_string(),
-            )))
+    let Some(repo) = open_repo(root, false)? else {
+        return Ok(None);
+    };
+    get_repo_head_of(&repo)
+}
+
+/// Like `get_repo_head()`, but reads from the already opened `repo`.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_head_of(
+    repo: &git2::Repository,
+) -> Result<Option<(Option<String>, String, String)>, git2::Error> {
+    // Supposed to be the reference pointed to by HEAD, but it's HEAD
+    // itself, if detached
+    let head_ref = repo.head()?;
+    let branch = {
+        // Check whether `head` is really the pointed to reference and
+        // not HEAD itself.
+        if repo.head_detached()? {
+            None
+        } else {
+            head_ref.name()
         }
+    };
+    let head = head_ref.peel_to_commit()?;
+    let commit = head.id();
+    let commit_short = head.into_object().short_id()?;
+    Ok(Some((
+        branch.map(ToString::to_string),
+        format!("{commit}"),
+        commit_short.as_str().unwrap_or_default().to_string(),
+    )))
+}
+
+/// Determines whether `commit` is reachable from any remote-tracking branch.
+///
+/// The result is `Some(true)` if `commit` is equal to or an ancestor of at least one of the
+/// references below `refs/remotes/`, `Some(false)` if it is not reachable from any of them.
+///
+/// If there are no remote-tracking branches at all, `Ok(None)` is returned.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_commit_on_remote(
+    repo: &git2::Repository,
+    commit: git2::Oid,
+) -> Result<Option<bool>, git2::Error> {
+    let mut has_remotes = false;
+    for reference in repo.references_glob("refs/remotes/*")? {
+        let Ok(remote) = reference?.peel_to_commit() else {
+            continue;
+        };
+        has_remotes = true;
+        if remote.id() == commit || repo.graph_descendant_of(remote.id(), commit)? {
+            return Ok(Some(true));
+        }
+    }
+    Ok(has_remotes.then_some(false))
+}
+
+/// Determines whether `commit` is reachable from the local branch named `branch`,
+/// i.e. if it is equal to or an ancestor of the branch's commit.
+///
+/// If there is no such branch, `Ok(None)` is returned.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_is_ancestor_of(
+    repo: &git2::Repository,
+    commit: git2::Oid,
+    branch: &str,
+) -> Result<Option<bool>, git2::Error> {
+    let base = match repo.find_branch(branch, git2::BranchType::Local) {
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+        base => base?.get().peel_to_commit()?.id(),
+    };
+    Ok(Some(
+        base == commit || repo.graph_descendant_of(base, commit)?,
+    ))
+}
+
+/// Retrieves the signing key of `commit`, without verifying the signature.
+///
+/// If `commit` is not signed, `Ok(None)` is returned.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_commit_signing_key(
+    repo: &git2::Repository,
+    commit: git2::Oid,
+) -> Result<Option<String>, git2::Error> {
+    match repo.extract_signature(&commit, None) {
+        Ok((signature, _)) => Ok(signature
+            .as_str()
+            .and_then(crate::git_shared::parse_signing_key)),
+        Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Counts the unique author-emails in the history reachable from `commit`, walking
+/// at most `limit` commits.
+///
+/// If the repository is a shallow clone, `Ok(None)` is returned.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_contributor_count(
+    repo: &git2::Repository,
+    commit: git2::Oid,
+    limit: Option<usize>,
+) -> Result<Option<usize>, git2::Error> {
+    if repo.is_shallow() {
+        return Ok(None);
+    }
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(commit)?;
+    let mut emails = std::collections::HashSet::new();
+    for oid in revwalk.take(limit.unwrap_or(usize::MAX)) {
+        emails.insert(repo.find_commit(oid?)?.author().email_bytes().to_owned());
+    }
+    Ok(Some(emails.len()))
+}
+
+/// Counts the commits in the history reachable from `commit`.
+///
+/// If the repository is a shallow clone, `Ok(None)` is returned.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_commits_count(
+    repo: &git2::Repository,
+    commit: git2::Oid,
+) -> Result<Option<usize>, git2::Error> {
+    if repo.is_shallow() {
+        return Ok(None);
+    }
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(commit)?;
+    let mut count = 0;
+    for oid in revwalk {
+        oid?;
+        count += 1;
+    }
+    Ok(Some(count))
+}
+
+/// Counts the commits on the first-parent chain from `commit` back to the root commit,
+/// including both; this is stable under merges, unlike the total number of commits.
+///
+/// If the repository is a shallow clone, `Ok(None)` is returned.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_commit_depth(
+    repo: &git2::Repository,
+    commit: git2::Oid,
+) -> Result<Option<usize>, git2::Error> {
+    if repo.is_shallow() {
+        return Ok(None);
+    }
+    let mut revwalk = repo.revwalk()?;
+    revwalk.simplify_first_parent()?;
+    revwalk.push(commit)?;
+    let mut depth = 0;
+    for oid in revwalk {
+        oid?;
+        depth += 1;
+    }
+    Ok(Some(depth))
+}
+
+/// Counts the files changed by `commit` compared to its first parent, like
+/// `git diff --name-only HEAD^ HEAD`. Renames are counted as a deletion and an addition.
+///
+/// If `commit` has no parent, `Ok(None)` is returned.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_commit_files_changed(
+    repo: &git2::Repository,
+    commit: git2::Oid,
+) -> Result<Option<usize>, git2::Error> {
+    let commit = repo.find_commit(commit)?;
+    if commit.parent_count() == 0 {
+        return Ok(None);
+    }
+    let parent = commit.parent(0)?;
+    let diff = repo.diff_tree_to_tree(Some(&parent.tree()?), Some(&commit.tree()?), None)?;
+    Ok(Some(diff.deltas().len()))
+}
+
+/// Retrieves the fetch-URL of the remote named `origin`, with any credentials removed.
+///
+/// If there is no remote named `origin`, `Ok(None)` is returned.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_remote_origin_url(
+    repo: &git2::Repository,
+) -> Result<Option<String>, git2::Error> {
+    let remote = match repo.find_remote("origin") {
+        Ok(remote) => remote,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    Ok(remote.url().map(crate::git_shared::strip_url_credentials))
+}
+
+/// Retrieves the name of the remote the current branch tracks, e.g. `origin`.
+///
+/// If HEAD is detached, or if the branch does not track a remote, `Ok(None)` is returned.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_upstream_remote(
+    repo: &git2::Repository,
+) -> Result<Option<String>, git2::Error> {
+    let head = match repo.head() {
+        Ok(head) => head,
+        Err(e)
+            if e.code() == git2::ErrorCode::UnbornBranch
+                || e.code() == git2::ErrorCode::NotFound =>
+        {
+            return Ok(None)
+        }
+        Err(e) => return Err(e),
+    };
+    let Some(name) = head.is_branch().then(|| head.name()).flatten() else {
+        return Ok(None);
+    };
+    match repo.branch_upstream_remote(name) {
+        Ok(remote) => Ok(remote.as_str().map(|s| s.to_owned())),
+        Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Retrieves the names of all tags pointing at `commit`, sorted by name.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_tags_at_head(
+    repo: &git2::Repository,
+    commit: git2::Oid,
+) -> Result<Option<Vec<String>>, git2::Error> {
+    let mut tags = Vec::new();
+    for reference in repo.references_glob("refs/tags/*")? {
+        let reference = reference?;
+        if reference.peel_to_commit().is_ok_and(|c| c.id() == commit) {
+            if let Some(name) = reference.shorthand() {
+                tags.push(name.to_owned());
+            }
+        }
+    }
+    tags.sort_unstable();
+    Ok(Some(tags))
+}
+
+/// Retrieves the names of all tags pointing at `commit` or any of its ancestors, sorted
+/// by the commit-date of their commits, the newest first, and by name if those are equal.
+///
+/// If the repository is a shallow clone, no tags are returned.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_reachable_tags(
+    repo: &git2::Repository,
+    commit: git2::Oid,
+) -> Result<Option<Vec<String>>, git2::Error> {
+    if repo.is_shallow() {
+        return Ok(Some(Vec::new()));
+    }
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(commit)?;
+    let reachable = revwalk.collect::<Result<std::collections::HashSet<_>, _>>()?;
+    let mut tags = Vec::new();
+    for reference in repo.references_glob("refs/tags/*")? {
+        let reference = reference?;
+        let Ok(tagged) = reference.peel_to_commit() else {
+            continue;
+        };
+        if reachable.contains(&tagged.id()) {
+            if let Some(name) = reference.shorthand() {
+                tags.push((tagged.time().seconds(), name.to_owned()));
+            }
+        }
+    }
+    tags.sort_unstable_by(|(a_time, a_name), (b_time, b_name)| {
+        b_time.cmp(a_time).then_with(|| a_name.cmp(b_name))
+    });
+    Ok(Some(tags.into_iter().map(|(_, name)| name).collect()))
+}
+
+/// Retrieves the message of the annotated tag pointing at `commit`; the first one by
+/// name if there are multiple.
+///
+/// If there is no annotated tag at `commit`, or if its message is not valid UTF-8, `Ok(None)` is
+/// returned.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_tag_message(
+    repo: &git2::Repository,
+    commit: git2::Oid,
+) -> Result<Option<String>, git2::Error> {
+    let mut tags = Vec::new();
+    for reference in repo.references_glob("refs/tags/*")? {
+        let reference = reference?;
+        if !reference.peel_to_commit().is_ok_and(|c| c.id() == commit) {
+            continue;
+        }
+        if let (Some(name), Ok(tag)) = (reference.shorthand(), reference.peel_to_tag()) {
+            tags.push((name.to_owned(), tag.message().map(ToOwned::to_owned)));
+        }
+    }
+    Ok(tags.into_iter().min().and_then(|(_, message)| message))
+}
+
+/// Retrieves the files and directories the information about the repository is derived
+/// from, i.e. `HEAD`, the index and the refs. Paths that do not exist are omitted.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_rerun_paths(
+    repo: &git2::Repository,
+) -> Result<Option<Vec<std::path::PathBuf>>, git2::Error> {
+    Ok(Some(crate::git_shared::rerun_paths(
+        repo.path(),
+        repo.commondir(),
+    )))
+}
+
+/// Retrieves the time of `commit` as seconds since the epoch, and the committer's
+/// timezone-offset in seconds.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(all(feature = "git2", feature = "chrono"))]
+pub(crate) fn get_repo_commit_time(
+    repo: &git2::Repository,
+    commit: git2::Oid,
+) -> Result<Option<(i64, i32)>, git2::Error> {
+    let time = repo.find_commit(commit)?.time();
+    Ok(Some((time.seconds(), time.offset_minutes() * 60)))
+}
+
+/// Retrieves the hash-algorithm of the repository's objects, i.e. `"sha1"` or `"sha256"`, as
+/// configured by `extensions.objectFormat`.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_object_format(
+    repo: &git2::Repository,
+) -> Result<Option<String>, git2::Error> {
+    let format = match repo
+        .config()?
+        .open_level(git2::ConfigLevel::Local)?
+        .get_string("extensions.objectformat")
+    {
+        Err(e) if e.code() == git2::ErrorCode::NotFound => "sha1".to_owned(),
+        format => format?.to_ascii_lowercase(),
+    };
+    Ok(Some(format))
+}
+
+/// Retrieves `SOURCE_TREE_HASH`, a hash over the paths, modes and contents of all files in
+/// `commit`'s tree, computed with `algorithm`. Submodules are not considered.
+///
+/// If the repository is bare or a shallow clone, `Ok(None)` is returned.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_source_tree_hash(
+    repo: &git2::Repository,
+    commit: git2::Oid,
+    algorithm: crate::HashAlgorithm,
+) -> Result<Option<String>, git2::Error> {
+    if repo.is_bare() || repo.is_shallow() {
+        return Ok(None);
+    }
+    let mut files = Vec::new();
+    repo.find_commit(commit)?
+        .tree()?
+        .walk(git2::TreeWalkMode::PreOrder, |dir, entry| {
+            if entry.kind() == Some(git2::ObjectType::Blob) {
+                let mut path = dir.as_bytes().to_vec();
+                path.extend_from_slice(entry.name_bytes());
+                files.push((path, entry.filemode() as u32, entry.id()));
+            }
+            git2::TreeWalkResult::Ok
+        })?;
+    files.sort();
+    let mut hasher = crate::git_shared::SourceTreeHasher::new(algorithm);
+    for (path, mode, id) in files {
+        hasher.add(&path, mode, repo.find_blob(id)?.content());
+    }
+    Ok(Some(hasher.finish()))
+}
+
+/// Retrieves the operation in progress, e.g. `"merge"` or `"rebase"`; `"clean"` if
+/// there is none.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_state(repo: &git2::Repository) -> Result<Option<String>, git2::Error> {
+    use git2::RepositoryState;
+
+    let state = match repo.state() {
+        RepositoryState::Clean => "clean",
+        RepositoryState::Merge => "merge",
+        RepositoryState::Revert => "revert",
+        RepositoryState::RevertSequence => "revert-sequence",
+        RepositoryState::CherryPick => "cherry-pick",
+        RepositoryState::CherryPickSequence => "cherry-pick-sequence",
+        RepositoryState::Bisect => "bisect",
+        RepositoryState::Rebase | RepositoryState::RebaseMerge => "rebase",
+        RepositoryState::RebaseInteractive => "rebase-interactive",
+        RepositoryState::ApplyMailbox => "apply-mailbox",
+        RepositoryState::ApplyMailboxOrRebase => "apply-mailbox-or-rebase",
+    };
+    Ok(Some(state.to_owned()))
+}
+
+/// Retrieves the number of local and remote-tracking branches and the number of tags.
+///
+/// # Errors
+/// Errors from `git2` are returned if the repository can't be read.
+#[cfg(feature = "git2")]
+pub(crate) fn get_repo_ref_counts(
+    repo: &git2::Repository,
+) -> Result<Option<(usize, usize)>, git2::Error> {
+    let branches = repo.branches(None)?.count();
+    let tags = repo.tag_names(None)?.len();
+    Ok(Some((branches, tags)))
+}
+
+/// Discovers the repository at or above `root`, or opens it at exactly `root` without walking
+/// its parents if `exact` is `true`, which all `get_repo_*()`-functions then read from, mapping
+/// "not found" to `Ok(None)`.
+///
+/// # Errors
+/// Errors from `git2` are returned if a repository exists, but can't be opened.
+#[cfg(feature = "git2")]
+pub(crate) fn open_repo(
+    root: &std::path::Path,
+    exact: bool,
+) -> Result<Option<git2::Repository>, git2::Error> {
+    let repo = if exact {
+        git2::Repository::open(root)
+    } else {
+        git2::Repository::discover(crate::git_shared::canonicalize(root))
+    };
+    match repo {
+        Ok(repo) => Ok(Some(repo)),
         Err(ref e)
             if e.class() == git2::ErrorClass::Repository
                 && e.code() == git2::ErrorCode::NotFound =>
@@ -5,44 +5,671 @@ use std::path;
 /// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
 /// to have one shared implementation with `git2` which exports these functions.
 #[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_head_of(
+    repo: &gix::Repository,
+) -> Result<Option<(Option<String>, String, String)>, Infallible> {
+    Ok(get_repo_head_inner(repo))
+}
+
+/// The counterpart of `git::get_repo_head()`, which discovers the repository at or above `root`.
+#[cfg(all(test, feature = "git2"))]
 pub(crate) fn get_repo_head(
-    manifest_location: &path::Path,
+    root: &path::Path,
 ) -> Result<Option<(Option<String>, String, String)>, Infallible> {
-    Ok(get_repo_head_inner(manifest_location))
+    Ok(open_repo(root, false)
+        .ok()
+        .flatten()
+        .and_then(|repo| get_repo_head_inner(&repo)))
+}
+
+/// The counterpart of `git::get_repo_description()`, which discovers the repository at or above
+/// `root`.
+#[cfg(all(test, feature = "git2"))]
+pub(crate) fn get_repo_description(
+    root: &path::Path,
+) -> Result<Option<(String, bool)>, Infallible> {
+    Ok(open_repo(root, false).ok().flatten().and_then(|repo| {
+        get_repo_description_inner(&repo, crate::DirtyStatuses::default(), false, &[])
+    }))
 }
 
 /// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
 /// to have one shared implementation with `git2` which exports these functions.
 #[cfg_attr(feature = "git2", allow(unused))]
-pub(crate) fn get_repo_description(
-    manifest_location: &path::Path,
+pub(crate) fn get_repo_description_with(
+    repo: &gix::Repository,
+    statuses: crate::DirtyStatuses,
+    describe_all: bool,
+    pathspecs: &[path::PathBuf],
 ) -> Result<Option<(String, bool)>, Infallible> {
-    Ok(get_repo_description_inner(manifest_location))
+    Ok(get_repo_description_inner(
+        repo,
+        statuses,
+        describe_all,
+        pathspecs,
+    ))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_commits_count(
+    repo: &gix::Repository,
+    commit: gix::ObjectId,
+) -> Result<Option<usize>, Infallible> {
+    Ok(get_repo_commits_count_inner(repo, commit))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_commit_depth(
+    repo: &gix::Repository,
+    commit: gix::ObjectId,
+) -> Result<Option<usize>, Infallible> {
+    Ok(get_repo_commit_depth_inner(repo, commit))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_commit_files_changed(
+    repo: &gix::Repository,
+    commit: gix::ObjectId,
+) -> Result<Option<usize>, Infallible> {
+    Ok(get_repo_commit_files_changed_inner(repo, commit))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_commit_notes(
+    repo: &gix::Repository,
+    commit: gix::ObjectId,
+) -> Result<Option<String>, Infallible> {
+    Ok(get_repo_commit_notes_inner(repo, commit))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_nearest_tag(
+    repo: &gix::Repository,
+    commit: gix::ObjectId,
+) -> Result<Option<String>, Infallible> {
+    Ok(get_repo_nearest_tag_inner(repo, commit))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_tag_commit(
+    repo: &gix::Repository,
+    tag: &str,
+) -> Result<Option<String>, Infallible> {
+    Ok(get_repo_tag_commit_inner(repo, tag))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_dirty_lines(
+    repo: &gix::Repository,
+) -> Result<Option<(usize, usize)>, Infallible> {
+    Ok(get_repo_dirty_lines_inner(repo))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_has_conflicts(repo: &gix::Repository) -> Result<Option<bool>, Infallible> {
+    Ok(get_repo_has_conflicts_inner(repo))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_dirty_files(
+    repo: &gix::Repository,
+) -> Result<Option<Vec<String>>, Infallible> {
+    Ok(get_repo_dirty_files_inner(repo))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_describe(
+    repo: &gix::Repository,
+    committish: &str,
+    describe_all: bool,
+) -> Result<Option<String>, Infallible> {
+    Ok(get_repo_describe_inner(repo, committish, describe_all))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_commit_id(
+    repo: &gix::Repository,
+    committish: &str,
+) -> Result<Option<gix::ObjectId>, Infallible> {
+    Ok(get_repo_commit_id_inner(repo, committish))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_commit(
+    repo: &gix::Repository,
+    commit: gix::ObjectId,
+) -> Result<Option<(String, String)>, Infallible> {
+    Ok(get_repo_commit_inner(repo, commit))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_commit_on_remote(
+    repo: &gix::Repository,
+    commit: gix::ObjectId,
+) -> Result<Option<bool>, Infallible> {
+    Ok(get_repo_commit_on_remote_inner(repo, commit))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_is_ancestor_of(
+    repo: &gix::Repository,
+    commit: gix::ObjectId,
+    branch: &str,
+) -> Result<Option<bool>, Infallible> {
+    Ok(get_repo_is_ancestor_of_inner(repo, commit, branch))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_commit_signing_key(
+    repo: &gix::Repository,
+    commit: gix::ObjectId,
+) -> Result<Option<String>, Infallible> {
+    Ok(get_repo_commit_signing_key_inner(repo, commit))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_contributor_count(
+    repo: &gix::Repository,
+    commit: gix::ObjectId,
+    limit: Option<usize>,
+) -> Result<Option<usize>, Infallible> {
+    Ok(get_repo_contributor_count_inner(repo, commit, limit))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_remote_origin_url(
+    repo: &gix::Repository,
+) -> Result<Option<String>, Infallible> {
+    Ok(get_repo_remote_origin_url_inner(repo))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_upstream_remote(
+    repo: &gix::Repository,
+) -> Result<Option<String>, Infallible> {
+    Ok(get_repo_upstream_remote_inner(repo))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_tags_at_head(
+    repo: &gix::Repository,
+    commit: gix::ObjectId,
+) -> Result<Option<Vec<String>>, Infallible> {
+    Ok(get_repo_tags_at_head_inner(repo, commit))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_rerun_paths(
+    repo: &gix::Repository,
+) -> Result<Option<Vec<path::PathBuf>>, Infallible> {
+    Ok(get_repo_rerun_paths_inner(repo))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg(feature = "chrono")]
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_commit_time(
+    repo: &gix::Repository,
+    commit: gix::ObjectId,
+) -> Result<Option<(i64, i32)>, Infallible> {
+    Ok(get_repo_commit_time_inner(repo, commit))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_object_format(repo: &gix::Repository) -> Result<Option<String>, Infallible> {
+    Ok(get_repo_object_format_inner(repo))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_source_tree_hash(
+    repo: &gix::Repository,
+    commit: gix::ObjectId,
+    algorithm: crate::HashAlgorithm,
+) -> Result<Option<String>, Infallible> {
+    Ok(get_repo_source_tree_hash_inner(repo, commit, algorithm))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_reachable_tags(
+    repo: &gix::Repository,
+    commit: gix::ObjectId,
+) -> Result<Option<Vec<String>>, Infallible> {
+    Ok(get_repo_reachable_tags_inner(repo, commit))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_tag_message(
+    repo: &gix::Repository,
+    commit: gix::ObjectId,
+) -> Result<Option<String>, Infallible> {
+    Ok(get_repo_tag_message_inner(repo, commit))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_state(repo: &gix::Repository) -> Result<Option<String>, Infallible> {
+    Ok(get_repo_state_inner(repo))
+}
+
+/// An adapter to add a `Result` around the `Option` we natively return. This is the easiest way
+/// to have one shared implementation with `git2` which exports these functions.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn get_repo_ref_counts(
+    repo: &gix::Repository,
+) -> Result<Option<(usize, usize)>, Infallible> {
+    Ok(get_repo_ref_counts_inner(repo))
 }
 
 /// Retrieves the git-tag or hash describing the exact version and a boolean
 /// that indicates if the repository currently has dirty/staged files.
 ///
-/// If a valid git-repo can't be discovered at or above the given path,
-/// or if any operation on the repository fails, `None` is returned.
-fn get_repo_description_inner(manifest_location: &path::Path) -> Option<(String, bool)> {
-    let repo = gix::discover(manifest_location).ok()?;
+/// Only the kinds of changes in `statuses` make the repository dirty. HEAD is described
+/// against all references, like `git describe --all`, if `describe_all` is `true`. Only the
+/// files matching `pathspecs`, relative to the repository's root, are considered unless it
+/// is empty.
+///
+/// If any operation on the repository fails, `None` is returned.
+fn get_repo_description_inner(
+    repo: &gix::Repository,
+    statuses: crate::DirtyStatuses,
+    describe_all: bool,
+    pathspecs: &[path::PathBuf],
+) -> Option<(String, bool)> {
+    use crate::DirtyStatuses;
+
     let commit = repo.head_commit().ok()?;
-    let tag = commit.describe().format().ok()?.to_string();
-    let dirty = repo.is_dirty().ok()?;
+    let tag = if describe_all {
+        describe_all_refs(&commit)?
+    } else {
+        commit.describe().format().ok()?.to_string()
+    };
+    let tracked = statuses == DirtyStatuses::default()
+        || statuses == DirtyStatuses::default() | DirtyStatuses::IGNORED;
+    let dirty = if !tracked {
+        has_dirty_statuses(repo, pathspecs, statuses)?
+    } else if pathspecs.is_empty() {
+        repo.is_dirty().ok()?
+    } else {
+        !dirty_paths_matching(repo, pathspecs)?.is_empty()
+    };
+    let dirty =
+        dirty || (statuses.contains(DirtyStatuses::IGNORED) && has_ignored_files(repo, pathspecs)?);
 
     Some((tag, dirty))
 }
 
+/// Retrieves the number of inserted and deleted lines of the working tree and the index,
+/// compared to HEAD, like `git diff HEAD --shortstat`. Untracked files and binary files
+/// are not considered.
+///
+/// If there are no changes, or if any operation on the repository fails, `None` is returned.
+fn get_repo_dirty_lines_inner(repo: &gix::Repository) -> Option<(usize, usize)> {
+    use gix::diff::blob::{diff, intern::InternedInput, sink::Counter, sources, Algorithm};
+
+    let workdir = repo.workdir()?;
+    let head_tree = repo.head_tree().ok()?;
+    let paths = dirty_paths(repo)?;
+    if paths.is_empty() {
+        return None;
+    }
+    let (mut insertions, mut deletions) = (0, 0);
+    for path in paths {
+        let path = gix::path::from_bstr(path.as_bstr());
+        let before = match head_tree.lookup_entry_by_path(&path).ok()? {
+            Some(entry) if entry.mode().is_blob() => entry.object().ok()?.detach().data,
+            _ => Vec::new(),
+        };
+        let after = std::fs::read(workdir.join(&path)).unwrap_or_default();
+        // Like `git`, don't count the lines of binary files.
+        if before.contains(&0) || after.contains(&0) {
+            continue;
+        }
+        let input = InternedInput::new(sources::byte_lines(&before), sources::byte_lines(&after));
+        let counter = diff(Algorithm::Histogram, &input, Counter::default());
+        insertions += counter.insertions as usize;
+        deletions += counter.removals as usize;
+    }
+    Some((insertions, deletions))
+}
+
+/// Determines whether the index has unmerged paths, i.e. unresolved merge-conflicts.
+///
+/// If the repository is bare, or if any operation on the repository fails, `None` is returned.
+fn get_repo_has_conflicts_inner(repo: &gix::Repository) -> Option<bool> {
+    repo.workdir()?;
+    let index = repo.index_or_empty().ok()?;
+    Some(index.entries().iter().any(|entry| entry.stage_raw() != 0))
+}
+
+/// Retrieves the paths of the files in the working tree and the index that differ from HEAD,
+/// relative to the repository's root and sorted by name. Untracked and ignored files are not
+/// considered.
+///
+/// If any operation on the repository fails, `None` is returned.
+fn get_repo_dirty_files_inner(repo: &gix::Repository) -> Option<Vec<String>> {
+    let paths = dirty_paths(repo)?;
+    Some(
+        paths
+            .iter()
+            .map(|path| path.to_str_lossy().into_owned())
+            .collect(),
+    )
+}
+
+/// The paths of the files in the working tree and the index that differ from HEAD, without
+/// untracked files and rename-tracking.
+fn dirty_paths(repo: &gix::Repository) -> Option<std::collections::BTreeSet<gix::bstr::BString>> {
+    dirty_paths_matching(repo, &[])
+}
+
+/// Like `dirty_paths()`, but only considers the files matching `pathspecs`, relative to the
+/// repository's root; all files if it is empty.
+fn dirty_paths_matching(
+    repo: &gix::Repository,
+    pathspecs: &[path::PathBuf],
+) -> Option<std::collections::BTreeSet<gix::bstr::BString>> {
+    repo.status(gix::progress::Discard)
+        .ok()?
+        .untracked_files(gix::status::UntrackedFiles::None)
+        .index_worktree_rewrites(None)
+        .tree_index_track_renames(gix::status::tree_index::TrackRenames::Disabled)
+        .into_iter(top_level_pathspecs(pathspecs))
+        .ok()?
+        .map(|item| item.ok().map(|item| item.location().to_owned()))
+        .collect()
+}
+
+/// Whether any file matching `pathspecs` has a change of a kind in `statuses`, not
+/// considering ignored files. All files are considered if `pathspecs` is empty.
+///
+/// If any operation on the repository fails, `None` is returned.
+fn has_dirty_statuses(
+    repo: &gix::Repository,
+    pathspecs: &[path::PathBuf],
+    statuses: crate::DirtyStatuses,
+) -> Option<bool> {
+    use crate::DirtyStatuses;
+    use gix::diff::index::ChangeRef;
+    use gix::status::plumbing::index_as_worktree::{Change, EntryStatus};
+    use gix::status::{index_worktree, Item};
+
+    // Like `git2`, a change from a regular to an executable file is not a change of type.
+    let is_type_change = |previous: gix::index::entry::Mode, current: gix::index::entry::Mode| {
+        let kind = |mode| match mode {
+            gix::index::entry::Mode::FILE_EXECUTABLE => gix::index::entry::Mode::FILE,
+            mode => mode,
+        };
+        kind(previous) != kind(current)
+    };
+    let kind_of = |item: Item| match item {
+        Item::TreeIndex(ChangeRef::Modification {
+            previous_entry_mode,
+            entry_mode,
+            ..
+        }) if is_type_change(previous_entry_mode, entry_mode) => DirtyStatuses::TYPECHANGE,
+        Item::TreeIndex(_) => DirtyStatuses::INDEX,
+        Item::IndexWorktree(index_worktree::Item::Modification { status, .. }) => match status {
+            EntryStatus::Conflict { .. } => DirtyStatuses::INDEX | DirtyStatuses::WORKTREE,
+            EntryStatus::Change(Change::Type { .. }) => DirtyStatuses::TYPECHANGE,
+            EntryStatus::Change(_) => DirtyStatuses::WORKTREE,
+            EntryStatus::NeedsUpdate(_) | EntryStatus::IntentToAdd => DirtyStatuses::empty(),
+        },
+        Item::IndexWorktree(index_worktree::Item::DirectoryContents { entry, .. })
+            if entry.status == gix::dir::entry::Status::Untracked =>
+        {
+            DirtyStatuses::UNTRACKED
+        }
+        Item::IndexWorktree(index_worktree::Item::DirectoryContents { .. }) => {
+            DirtyStatuses::empty()
+        }
+        Item::IndexWorktree(index_worktree::Item::Rewrite { .. }) => DirtyStatuses::UNTRACKED,
+    };
+
+    let untracked = if statuses.contains(DirtyStatuses::UNTRACKED) {
+        gix::status::UntrackedFiles::Collapsed
+    } else {
+        gix::status::UntrackedFiles::None
+    };
+    let mut items = repo
+        .status(gix::progress::Discard)
+        .ok()?
+        .untracked_files(untracked)
+        .index_worktree_rewrites(None)
+        .tree_index_track_renames(gix::status::tree_index::TrackRenames::Disabled)
+        .into_iter(top_level_pathspecs(pathspecs))
+        .ok()?;
+    items.try_fold(false, |dirty, item| {
+        Some(dirty || statuses.intersects(kind_of(item.ok()?)))
+    })
+}
+
+/// Turns `pathspecs` into patterns relative to the repository's root, like `git2` interprets
+/// them, instead of relative to the current working directory.
+fn top_level_pathspecs(pathspecs: &[path::PathBuf]) -> Vec<gix::bstr::BString> {
+    pathspecs
+        .iter()
+        .map(|pathspec| {
+            let mut pattern = gix::bstr::BString::from(":(top)");
+            pattern.extend_from_slice(&gix::path::to_unix_separators_on_windows(
+                gix::path::into_bstr(pathspec.as_path()),
+            ));
+            pattern
+        })
+        .collect()
+}
+
+/// Retrieves the git-tag or hash describing `committish`, e.g. `origin/main`, instead of HEAD.
+/// All references are considered, like `git describe --all`, if `describe_all` is `true`.
+///
+/// If `committish` can't be resolved, `None` is returned.
+fn get_repo_describe_inner(
+    repo: &gix::Repository,
+    committish: &str,
+    describe_all: bool,
+) -> Option<String> {
+    let commit = repo
+        .rev_parse_single(committish)
+        .ok()?
+        .object()
+        .ok()?
+        .peel_to_commit()
+        .ok()?;
+    if describe_all {
+        return describe_all_refs(&commit);
+    }
+    // Like `git2`, consider lightweight tags as well.
+    let mut describe = commit
+        .describe()
+        .names(gix::commit::describe::SelectRef::AllTags);
+    Some(describe.format().ok()?.to_string())
+}
+
+/// Describes `commit` against all references, like `git describe --all`, falling back to
+/// the short commit id.
+fn describe_all_refs(commit: &gix::Commit<'_>) -> Option<String> {
+    let mut format = commit
+        .describe()
+        .names(gix::commit::describe::SelectRef::AllRefs)
+        .format()
+        .ok()?;
+    // `gix` shortens the names, e.g. to `main`, while `git` shows them relative to `refs/`,
+    // e.g. as `heads/main`.
+    if let Some(name) = format.name.as_mut() {
+        let reference = commit.repo.find_reference(name.as_ref()).ok()?;
+        let full_name = reference.name().as_bstr();
+        *name = full_name
+            .strip_prefix(b"refs/")
+            .unwrap_or(full_name)
+            .as_bstr()
+            .to_owned()
+            .into();
+    }
+    Some(format.to_string())
+}
+
+/// Resolves `committish`, e.g. `HEAD` or `v1.0.0`, to the id of the commit it refers to, which
+/// the commit-scoped `get_repo_*()`-functions then read from.
+///
+/// If `committish` can't be resolved, or if any operation on the repository fails, `None` is
+/// returned.
+fn get_repo_commit_id_inner(repo: &gix::Repository, committish: &str) -> Option<gix::ObjectId> {
+    let commit = repo
+        .rev_parse_single(committish)
+        .ok()?
+        .object()
+        .ok()?
+        .peel_to_commit()
+        .ok()?;
+    Some(commit.id)
+}
+
+/// Retrieves the long- and short-hash of `commit`.
+///
+/// If any operation on the repository fails, `None` is returned.
+fn get_repo_commit_inner(
+    repo: &gix::Repository,
+    commit: gix::ObjectId,
+) -> Option<(String, String)> {
+    let commit_id = repo.find_commit(commit).ok()?.id();
+    let ids = (commit_id.to_string(), commit_id.shorten_or_id().to_string());
+    Some(ids)
+}
+
+/// Retrieves the name of the most recent tag reachable from `commit`, like
+/// `git describe --tags --abbrev=0`.
+///
+/// If no tag is reachable, or if any operation on the repository fails, `None` is returned.
+fn get_repo_nearest_tag_inner(repo: &gix::Repository, commit: gix::ObjectId) -> Option<String> {
+    let commit = repo.find_commit(commit).ok()?;
+    let resolution = commit
+        .describe()
+        .names(gix::commit::describe::SelectRef::AllTags)
+        .try_resolve()
+        .ok()??;
+    Some(resolution.outcome.name?.to_string())
+}
+
+/// Retrieves the full hash of the commit the tag `tag` points to.
+///
+/// If there is no such tag, or if any operation on the repository fails, `None` is returned.
+fn get_repo_tag_commit_inner(repo: &gix::Repository, tag: &str) -> Option<String> {
+    let mut reference = repo
+        .find_reference(format!("refs/tags/{tag}").as_str())
+        .ok()?;
+    let commit = reference.peel_to_commit().ok()?;
+    Some(commit.id.to_string())
+}
+
+/// Retrieves the note attached to `commit` in the default notes-ref, i.e.
+/// `core.notesRef` or `refs/notes/commits`.
+///
+/// If there is no note, if the note is not valid UTF-8, or if any operation on the repository
+/// fails, `None` is returned.
+fn get_repo_commit_notes_inner(repo: &gix::Repository, commit: gix::ObjectId) -> Option<String> {
+    let commit_id = commit.to_hex().to_string();
+    let notes_ref = repo
+        .config_snapshot()
+        .string("core.notesRef")
+        .map_or_else(|| "refs/notes/commits".to_owned(), |r| r.to_string());
+    let tree = repo
+        .find_reference(notes_ref.as_str())
+        .ok()?
+        .peel_to_commit()
+        .ok()?
+        .tree()
+        .ok()?;
+    // Notes are stored by the commit's id, possibly fanned out into directories named after
+    // its leading bytes, e.g. `ab/cdef...`.
+    let entry = (0..commit_id.len() / 2).find_map(|depth| {
+        let mut path = String::new();
+        for i in 0..depth {
+            path.push_str(&commit_id[i * 2..i * 2 + 2]);
+            path.push('/');
+        }
+        path.push_str(&commit_id[depth * 2..]);
+        tree.lookup_entry_by_path(path).ok()?
+    })?;
+    let note = String::from_utf8(entry.object().ok()?.detach().data).ok();
+    note
+}
+
+/// Determines whether the worktree contains any files ignored by `.gitignore` and friends.
+fn has_ignored_files(repo: &gix::Repository, pathspecs: &[path::PathBuf]) -> Option<bool> {
+    let options = repo
+        .dirwalk_options()
+        .ok()?
+        .emit_ignored(Some(gix::dir::walk::EmissionMode::CollapseDirectory));
+    let should_interrupt = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let mut entries = repo
+        .dirwalk_iter(
+            repo.index_or_empty().ok()?,
+            top_level_pathspecs(pathspecs),
+            should_interrupt.into(),
+            options,
+        )
+        .ok()?;
+    entries.try_fold(false, |found, item| {
+        Some(found || matches!(item.ok()?.entry.status, gix::dir::entry::Status::Ignored(_)))
+    })
+}
+
 /// Retrieves the branch name and hash of HEAD.
 ///
 /// The returned value is a tuple of head's reference-name, long-hash and short-hash. The
 /// branch name will be `None` if the head is detached, or it's not valid UTF-8.
 ///
-/// If a valid git-repo can't be discovered at or above the given path,
-/// or if any operation on the repository fails, `None` is returned.
-fn get_repo_head_inner(manifest_location: &path::Path) -> Option<(Option<String>, String, String)> {
-    let repo = gix::discover(manifest_location).ok()?;
+/// If any operation on the repository fails, `None` is returned.
+fn get_repo_head_inner(repo: &gix::Repository) -> Option<(Option<String>, String, String)> {
     let mut head = repo.head().ok()?;
     let branch = head
         .clone()
@@ -53,3 +680,367 @@ fn get_repo_head_inner(manifest_location: &path::Path) -> Option<(Option<String>
 
     Some((branch, commit_id.to_string(), commit_id_short))
 }
+
+/// Determines whether `commit` is reachable from any remote-tracking branch.
+///
+/// If there are no remote-tracking branches at all, or if any operation on the repository
+/// fails, `None` is returned.
+fn get_repo_commit_on_remote_inner(repo: &gix::Repository, commit: gix::ObjectId) -> Option<bool> {
+    let mut has_remotes = false;
+    for mut remote in repo.references().ok()?.remote_branches().ok()?.flatten() {
+        let Ok(remote) = remote.peel_to_commit() else {
+            continue;
+        };
+        has_remotes = true;
+        if remote.id == commit
+            || repo
+                .merge_base(commit, remote.id)
+                .is_ok_and(|base| base == commit)
+        {
+            return Some(true);
+        }
+    }
+    has_remotes.then_some(false)
+}
+
+/// Determines whether `commit` is reachable from the local branch named `branch`,
+/// i.e. if it is equal to or an ancestor of the branch's commit.
+///
+/// If there is no such branch, or if any operation on the repository fails, `None` is
+/// returned.
+fn get_repo_is_ancestor_of_inner(
+    repo: &gix::Repository,
+    commit: gix::ObjectId,
+    branch: &str,
+) -> Option<bool> {
+    let base = repo
+        .find_reference(format!("refs/heads/{branch}").as_str())
+        .ok()?
+        .peel_to_commit()
+        .ok()?
+        .id;
+    Some(base == commit || repo.merge_base(commit, base).is_ok_and(|b| b == commit))
+}
+
+/// Retrieves the signing key of `commit`, without verifying the signature.
+///
+/// If `commit` is not signed, or if any operation on the repository fails, `None` is
+/// returned.
+fn get_repo_commit_signing_key_inner(
+    repo: &gix::Repository,
+    commit: gix::ObjectId,
+) -> Option<String> {
+    let commit = repo.find_commit(commit).ok()?;
+    let (signature, _) = commit.signature().ok()??;
+    crate::git_shared::parse_signing_key(signature.to_str().ok()?)
+}
+
+/// Counts the unique author-emails in the history reachable from `commit`, walking
+/// at most `limit` commits.
+///
+/// If the repository is a shallow clone, or if any operation on the repository fails, `None`
+/// is returned.
+fn get_repo_contributor_count_inner(
+    repo: &gix::Repository,
+    commit: gix::ObjectId,
+    limit: Option<usize>,
+) -> Option<usize> {
+    if repo.is_shallow() {
+        return None;
+    }
+    let mut emails = std::collections::HashSet::new();
+    for info in repo
+        .find_commit(commit)
+        .ok()?
+        .ancestors()
+        .all()
+        .ok()?
+        .take(limit.unwrap_or(usize::MAX))
+    {
+        let ancestor = info.ok()?.object().ok()?;
+        emails.insert(ancestor.author().ok()?.email.to_owned());
+    }
+    Some(emails.len())
+}
+
+/// Counts the commits in the history reachable from `commit`.
+///
+/// If the repository is a shallow clone, or if any operation on the repository fails, `None`
+/// is returned.
+fn get_repo_commits_count_inner(repo: &gix::Repository, commit: gix::ObjectId) -> Option<usize> {
+    if repo.is_shallow() {
+        return None;
+    }
+    let count = repo
+        .find_commit(commit)
+        .ok()?
+        .ancestors()
+        .all()
+        .ok()?
+        .try_fold(0, |count, info| info.ok().map(|_| count + 1));
+    count
+}
+
+/// Counts the commits on the first-parent chain from `commit` back to the root commit,
+/// including both.
+///
+/// If the repository is a shallow clone, or if any operation on the repository fails, `None`
+/// is returned.
+fn get_repo_commit_depth_inner(repo: &gix::Repository, commit: gix::ObjectId) -> Option<usize> {
+    if repo.is_shallow() {
+        return None;
+    }
+    let depth = repo
+        .find_commit(commit)
+        .ok()?
+        .ancestors()
+        .first_parent_only()
+        .all()
+        .ok()?
+        .try_fold(0, |depth, info| info.ok().map(|_| depth + 1));
+    depth
+}
+
+/// Counts the files changed by `commit` compared to its first parent, like
+/// `git diff --name-only HEAD^ HEAD`. Renames are counted as a deletion and an addition.
+///
+/// If `commit` has no parent, or if any operation on the repository fails, `None` is
+/// returned.
+fn get_repo_commit_files_changed_inner(
+    repo: &gix::Repository,
+    commit: gix::ObjectId,
+) -> Option<usize> {
+    let commit = repo.find_commit(commit).ok()?;
+    let parent_id = commit.parent_ids().next()?;
+    let parent_tree = repo.find_commit(parent_id).ok()?.tree().ok()?;
+    let tree = commit.tree().ok()?;
+    let changes = repo
+        .diff_tree_to_tree(&parent_tree, &tree, gix::diff::Options::default())
+        .ok()?;
+    Some(
+        changes
+            .iter()
+            .filter(|change| !change.entry_mode().is_tree())
+            .count(),
+    )
+}
+
+/// Retrieves the fetch-URL of the remote named `origin`, with any credentials removed.
+///
+/// If there is no remote named `origin`, or if any operation on the repository fails, `None`
+/// is returned.
+fn get_repo_remote_origin_url_inner(repo: &gix::Repository) -> Option<String> {
+    let remote = repo.find_remote("origin").ok()?;
+    let url = remote.url(gix::remote::Direction::Fetch)?.to_bstring();
+    Some(crate::git_shared::strip_url_credentials(url.to_str().ok()?))
+}
+
+/// Retrieves the name of the remote the current branch tracks, e.g. `origin`.
+///
+/// If HEAD is detached, if the branch does not track a remote, or if any operation on the
+/// repository fails, `None` is returned.
+fn get_repo_upstream_remote_inner(repo: &gix::Repository) -> Option<String> {
+    let head = repo.head_name().ok()??;
+    let remote = repo.branch_remote_name(head.shorten(), gix::remote::Direction::Fetch)?;
+    Some(remote.as_bstr().to_str().ok()?.to_owned())
+}
+
+/// Retrieves the names of all tags pointing at `commit`, sorted by name.
+///
+/// If any operation on the repository fails, `None` is returned.
+fn get_repo_tags_at_head_inner(
+    repo: &gix::Repository,
+    commit: gix::ObjectId,
+) -> Option<Vec<String>> {
+    let mut tags = Vec::new();
+    for mut reference in repo.references().ok()?.tags().ok()?.flatten() {
+        if reference.peel_to_commit().is_ok_and(|c| c.id == commit) {
+            if let Ok(name) = reference.name().shorten().to_str() {
+                tags.push(name.to_owned());
+            }
+        }
+    }
+    tags.sort_unstable();
+    Some(tags)
+}
+
+/// Retrieves the names of all tags pointing at `commit` or any of its ancestors, sorted
+/// by the commit-date of their commits, the newest first, and by name if those are equal.
+///
+/// If any operation on the repository fails, `None` is returned. If the repository is a
+/// shallow clone, no tags are returned.
+fn get_repo_reachable_tags_inner(
+    repo: &gix::Repository,
+    commit: gix::ObjectId,
+) -> Option<Vec<String>> {
+    if repo.is_shallow() {
+        return Some(Vec::new());
+    }
+    let reachable = repo
+        .find_commit(commit)
+        .ok()?
+        .ancestors()
+        .all()
+        .ok()?
+        .map(|info| info.map(|info| info.id))
+        .collect::<Result<std::collections::HashSet<_>, _>>()
+        .ok()?;
+    let mut tags = Vec::new();
+    for mut reference in repo.references().ok()?.tags().ok()?.flatten() {
+        let Ok(tagged) = reference.peel_to_commit() else {
+            continue;
+        };
+        if reachable.contains(&tagged.id) {
+            if let Ok(name) = reference.name().shorten().to_str() {
+                tags.push((tagged.time().ok()?.seconds, name.to_owned()));
+            }
+        }
+    }
+    tags.sort_unstable_by(|(a_time, a_name), (b_time, b_name)| {
+        b_time.cmp(a_time).then_with(|| a_name.cmp(b_name))
+    });
+    Some(tags.into_iter().map(|(_, name)| name).collect())
+}
+
+/// Retrieves the message of the annotated tag pointing at `commit`; the first one by
+/// name if there are multiple.
+///
+/// If there is no annotated tag at `commit`, if its message is not valid UTF-8, or if any
+/// operation on the repository fails, `None` is returned.
+fn get_repo_tag_message_inner(repo: &gix::Repository, commit: gix::ObjectId) -> Option<String> {
+    let mut tags = Vec::new();
+    for mut reference in repo.references().ok()?.tags().ok()?.flatten() {
+        if !reference.peel_to_commit().is_ok_and(|c| c.id == commit) {
+            continue;
+        }
+        let Some(tag) = reference
+            .target()
+            .try_id()
+            .and_then(|id| repo.find_object(id).ok()?.try_into_tag().ok())
+        else {
+            continue;
+        };
+        let message = tag
+            .decode()
+            .ok()
+            .and_then(|tag| Some(tag.message.to_str().ok()?.to_owned()));
+        tags.push((reference.name().shorten().to_owned(), message));
+    }
+    tags.into_iter().min()?.1
+}
+
+/// Retrieves the files and directories the information about the repository is derived
+/// from, i.e. `HEAD`, the index and the refs. Paths that do not exist are omitted.
+fn get_repo_rerun_paths_inner(repo: &gix::Repository) -> Option<Vec<path::PathBuf>> {
+    Some(crate::git_shared::rerun_paths(
+        repo.git_dir(),
+        repo.common_dir(),
+    ))
+}
+
+/// Retrieves the time of `commit` as seconds since the epoch, and the committer's
+/// timezone-offset in seconds.
+///
+/// If any operation on the repository fails, `None` is returned.
+#[cfg(feature = "chrono")]
+fn get_repo_commit_time_inner(repo: &gix::Repository, commit: gix::ObjectId) -> Option<(i64, i32)> {
+    let time = repo.find_commit(commit).ok()?.time().ok()?;
+    Some((time.seconds, time.offset))
+}
+
+/// Retrieves the hash-algorithm of the repository's objects, i.e. `"sha1"` or `"sha256"`.
+fn get_repo_object_format_inner(repo: &gix::Repository) -> Option<String> {
+    Some(repo.object_hash().to_string())
+}
+
+/// Retrieves `SOURCE_TREE_HASH`, a hash over the paths, modes and contents of all files in
+/// `commit`'s tree, computed with `algorithm`. Submodules are not considered.
+///
+/// If the repository is bare or a shallow clone, or if any operation on the repository fails,
+/// `None` is returned.
+fn get_repo_source_tree_hash_inner(
+    repo: &gix::Repository,
+    commit: gix::ObjectId,
+    algorithm: crate::HashAlgorithm,
+) -> Option<String> {
+    if repo.is_bare() || repo.is_shallow() {
+        return None;
+    }
+    let mut recorder = gix::traverse::tree::Recorder::default();
+    repo.find_commit(commit)
+        .ok()?
+        .tree()
+        .ok()?
+        .traverse()
+        .breadthfirst(&mut recorder)
+        .ok()?;
+    let mut files = recorder
+        .records
+        .into_iter()
+        .filter(|entry| entry.mode.is_blob_or_symlink())
+        .collect::<Vec<_>>();
+    files.sort_by(|a, b| a.filepath.cmp(&b.filepath));
+    let mut hasher = crate::git_shared::SourceTreeHasher::new(algorithm);
+    for entry in files {
+        let blob = repo.find_object(entry.oid).ok()?;
+        hasher.add(&entry.filepath, u32::from(entry.mode.value()), &blob.data);
+    }
+    Some(hasher.finish())
+}
+
+/// Retrieves the operation in progress, e.g. `"merge"` or `"rebase"`; `"clean"` if
+/// there is none.
+fn get_repo_state_inner(repo: &gix::Repository) -> Option<String> {
+    use gix::state::InProgress;
+
+    let state = match repo.state() {
+        None => "clean",
+        Some(InProgress::Merge) => "merge",
+        Some(InProgress::Revert) => "revert",
+        Some(InProgress::RevertSequence) => "revert-sequence",
+        Some(InProgress::CherryPick) => "cherry-pick",
+        Some(InProgress::CherryPickSequence) => "cherry-pick-sequence",
+        Some(InProgress::Bisect) => "bisect",
+        Some(InProgress::Rebase) => "rebase",
+        Some(InProgress::RebaseInteractive) => "rebase-interactive",
+        Some(InProgress::ApplyMailbox) => "apply-mailbox",
+        Some(InProgress::ApplyMailboxRebase) => "apply-mailbox-or-rebase",
+    };
+    Some(state.to_owned())
+}
+
+/// Retrieves the number of local and remote-tracking branches and the number of tags.
+///
+/// If any operation on the repository fails, `None` is returned.
+fn get_repo_ref_counts_inner(repo: &gix::Repository) -> Option<(usize, usize)> {
+    let references = repo.references().ok()?;
+    let branches =
+        references.local_branches().ok()?.count() + references.remote_branches().ok()?.count();
+    let tags = references.tags().ok()?.count();
+    Some((branches, tags))
+}
+
+/// Discovers the repository at or above `manifest_location`, or opens it at exactly
+/// `manifest_location` without walking its parents if `exact` is `true`, which all
+/// `get_repo_*()`-functions then read from, mapping "not found" to `Ok(None)`.
+///
+/// # Errors
+/// Errors from `gix` are returned if a repository exists, but can't be opened.
+#[cfg_attr(feature = "git2", allow(unused))]
+pub(crate) fn open_repo(
+    manifest_location: &path::Path,
+    exact: bool,
+) -> Result<Option<gix::Repository>, Box<gix::discover::Error>> {
+    if exact {
+        return match gix::open(manifest_location) {
+            Ok(repo) => Ok(Some(repo)),
+            Err(gix::open::Error::NotARepository { .. }) => Ok(None),
+            Err(e) => Err(Box::new(e.into())),
+        };
+    }
+    match gix::discover(crate::git_shared::canonicalize(manifest_location)) {
+        Ok(repo) => Ok(Some(repo)),
+        Err(gix::discover::Error::Discover(_)) => Ok(None),
+        Err(e) => Err(Box::new(e)),
+    }
+}
@@ -1,5 +1,5 @@
-use crate::{environment, util, write_str_variable, write_variable};
-use std::{fs, io};
+use crate::{environment, util, write_str_variable, write_variable, BuiltWriter};
+use std::io;
 
 impl<'a> util::ParseFromEnv<'a> for chrono::DateTime<chrono::offset::Utc> {
     type Err = chrono::ParseError;
@@ -53,8 +53,24 @@ fn get_source_date_epoch_from_env() -> Option<chrono::DateTime<chrono::offset::U
     }
 }
 
-pub fn write_time(mut w: &fs::File, envmap: &environment::EnvironmentMap) -> io::Result<()> {
-    use io::Write;
+/// Whether the local timezone can be determined; `chrono` silently falls back to UTC if
+/// it can't.
+fn local_timezone_known() -> bool {
+    if cfg!(unix) {
+        std::env::var_os("TZ").is_some_and(|tz| !tz.is_empty())
+            || std::path::Path::new("/etc/localtime").exists()
+    } else {
+        cfg!(windows)
+    }
+}
+
+pub fn write_time(
+    w: &mut BuiltWriter<'_>,
+    envmap: &environment::EnvironmentMap,
+    components: bool,
+    local: bool,
+) -> io::Result<()> {
+    use chrono::Datelike;
 
     let now = match envmap.get_override_var("BUILT_TIME_UTC") {
         Some(v) => v,
@@ -66,5 +82,81 @@ pub fn write_time(mut w: &fs::File, envmap: &environment::EnvironmentMap) -> io:
         now.to_rfc2822(),
         "The build time in RFC2822, UTC."
     );
+
+    if local {
+        let local = match envmap.get_override_var("BUILT_TIME_LOCAL") {
+            Some(v) => v,
+            // The timezone of the build-machine is not reproducible
+            None if std::env::var(crate::SOURCE_DATE_EPOCH).is_ok() => Some(now.to_rfc2822()),
+            None => local_timezone_known()
+                .then(|| now.with_timezone(&chrono::offset::Local).to_rfc2822()),
+        };
+        write_variable!(
+            w,
+            "BUILT_TIME_LOCAL",
+            "Option<&str>",
+            crate::fmt_option_str(local),
+            "The build time in RFC2822, in the local timezone of the build-machine; UTC if \
+            `SOURCE_DATE_EPOCH` is set, `None` if the timezone can't be determined."
+        );
+    }
+
+    if components {
+        write_variable!(
+            w,
+            "BUILT_YEAR",
+            "i32",
+            now.year(),
+            "The year of the build time, UTC."
+        );
+        write_variable!(
+            w,
+            "BUILT_MONTH",
+            "u32",
+            now.month(),
+            "The month of the build time, UTC, starting at `1`."
+        );
+        write_variable!(
+            w,
+            "BUILT_DAY",
+            "u32",
+            now.day(),
+            "The day of the month of the build time, UTC, starting at `1`."
+        );
+        write_variable!(
+            w,
+            "BUILT_ISO_WEEK",
+            "u32",
+            now.iso_week().week(),
+            "The ISO 8601 week-number of the build time, UTC, starting at `1`. Notice \
+        that around new year, the week may belong to the previous or the next year."
+        );
+    }
     Ok(())
 }
+
+/// Renders a commit's time, given as seconds since the epoch and the committer's
+/// timezone-offset in seconds.
+///
+/// # Panics
+/// If `format` is `TimeFormat::Custom` with an invalid format-string.
+#[cfg(any(feature = "git2", feature = "gix"))]
+pub(crate) fn format_commit_time(
+    seconds: i64,
+    offset: i32,
+    format: &crate::TimeFormat,
+) -> Option<String> {
+    use std::fmt::Write;
+
+    let time = chrono::DateTime::from_timestamp(seconds, 0)?
+        .with_timezone(&chrono::FixedOffset::east_opt(offset)?);
+    Some(match format {
+        crate::TimeFormat::Original => time.to_rfc2822(),
+        crate::TimeFormat::Utc => time.with_timezone(&chrono::offset::Utc).to_rfc2822(),
+        crate::TimeFormat::Custom(format) => {
+            let mut s = String::new();
+            write!(s, "{}", time.format(format)).expect("Invalid format for GIT_COMMIT_DATE");
+            s
+        }
+    })
+}
@@ -154,6 +154,21 @@
 //!
 //! Please refer to the respective item's documentation for more information on overrides.
 //!
+//! ## Options
+//!
+//! By default, `built` collects everything the enabled feature-flags make available. Use
+//! [`Options`] and [`write_built_file_with_options()`] to turn off groups of variables that
+//! are not needed; [`Options::minimal()`] is a convenient starting point for size-conscious
+//! builds, `Options::git_only()` writes nothing but the git-information.
+//!
+//! [`write_built_env()`] writes the same information as `KEY=VALUE`-lines instead, so
+//! shell-scripts or Docker can consume it without parsing Rust. [`Options::collect_map()`]
+//! returns the same information as a map, without writing any file.
+//! [`Options::set_emit_map()`] writes that map to `built.rs` as `BUILT_MAP`, to look up
+//! variables by name at runtime, and [`Options::set_emit_macro()`] writes a `build_info!`
+//! macro, to refer to variables as e.g. `build_info!(pkg_version)`. [`Options::set_compact()`]
+//! leaves out all doc comments, to shrink the generated file.
+//!
 //! ## Feature flags
 //! The information that `built` collects and makes available in `built.rs` depends
 //! on the features that were enabled on the build-time dependency.
@@ -201,6 +216,21 @@
 //! /// The source repository as advertised in Cargo.toml.
 //! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_PKG_REPOSITORY`.
 //! pub static PKG_REPOSITORY: &str = "";
+//! /// The source the package was downloaded from by cargo, e.g.
+//! /// `registry+https://github.com/rust-lang/crates.io-index`; `None` for path/local packages.
+//! /// This is a best-effort guess, based on the manifest residing in cargo's
+//! /// registry- or git-cache.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_PKG_SOURCE`.
+//! pub static PKG_SOURCE: Option<&str> = None;
+//! /// The minimum supported Rust version, as given by `rust-version` in Cargo.toml.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_PKG_RUST_VERSION`.
+//! pub static PKG_RUST_VERSION: Option<&str> = Some("1.70");
+//! /// The major version of `PKG_RUST_VERSION`.
+//! pub static PKG_RUST_VERSION_MAJOR: Option<u64> = Some(1);
+//! /// The minor version of `PKG_RUST_VERSION`; `0` if not given.
+//! pub static PKG_RUST_VERSION_MINOR: Option<u64> = Some(70);
+//! /// The patch version of `PKG_RUST_VERSION`; `0` if not given.
+//! pub static PKG_RUST_VERSION_PATCH: Option<u64> = Some(0);
 //!
 //! /// The target triple that was being compiled for.
 //! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_TARGET`.
@@ -208,6 +238,9 @@
 //! /// The host triple of the rust compiler.
 //! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_HOST`.
 //! pub static HOST: &str = "x86_64-unknown-linux-gnu";
+//! /// `true` if `TARGET` differs from `HOST`, i.e. if the crate was cross-compiled.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_IS_CROSS_COMPILE`.
+//! pub static IS_CROSS_COMPILE: bool = false;
 //! /// `release` for release builds, `debug` for other builds.
 //! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_PROFILE`.
 //! pub static PROFILE: &str = "debug";
@@ -226,6 +259,31 @@
 //! /// The output of `rustdoc -V`
 //! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_RUSTDOC_VERSION`.
 //! pub static RUSTDOC_VERSION: &str = "rustdoc 1.43.1 (8d69840ab 2020-05-04)";
+//! /// The output of `cargo -V`; `None` if `cargo -V` failed to execute.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_CARGO_VERSION`.
+//! pub static CARGO_VERSION: Option<&str> = Some("cargo 1.43.0 (2cbe9048e 2020-05-03)");
+//! /// The major version of cargo, as parsed from `CARGO_VERSION`.
+//! pub static CARGO_VERSION_MAJOR: Option<u64> = Some(1);
+//! /// The minor version of cargo, as parsed from `CARGO_VERSION`.
+//! pub static CARGO_VERSION_MINOR: Option<u64> = Some(43);
+//! /// The commit-date of the compiler as reported by `rustc -vV`, e.g. `2024-08-16`;
+//! /// `None` if the toolchain does not report it, e.g. a custom build.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_RUSTC_COMMIT_DATE`.
+//! pub static RUSTC_COMMIT_DATE: Option<&str> = Some("2020-05-04");
+//! /// The release-date of the compiler, derived from `RUSTC_COMMIT_DATE`: The day after
+//! /// for a nightly, as in `nightly-2024-08-17`, the same day otherwise.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_RUSTC_RELEASE_DATE`.
+//! pub static RUSTC_RELEASE_DATE: Option<&str> = Some("2020-05-04");
+//! /// The toolchain pinned by the nearest `rust-toolchain.toml` or `rust-toolchain`-file
+//! /// at or above the manifest, e.g. `1.75.0` or `nightly-2024-01-01`; `None` if there is
+//! /// none. This is the toolchain the project intends to use, not necessarily the one
+//! /// given by `RUSTC_VERSION`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_RUST_TOOLCHAIN_CHANNEL`.
+//! pub static RUST_TOOLCHAIN_CHANNEL: Option<&str> = None;
+//! /// The output of `rustc --print sysroot`, a path on the build-machine; `None` if `rustc`
+//! /// could not be run. Only written if enabled via `Options::set_rustc_sysroot()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_RUSTC_SYSROOT`.
+//! pub static RUSTC_SYSROOT: Option<&str> = Some("/home/user/.rustup/toolchains/stable-x86_64-unknown-linux-gnu");
 //!
 //! /// Value of `OPT_LEVEL` for the profile used during compilation.
 //! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_OPT_LEVEL`.
@@ -237,6 +295,10 @@
 //! /// "Value of DEBUG for the profile used during compilation.
 //! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_NUM_DEBUG`.
 //! pub static DEBUG: bool = true;
+//! /// Whether incremental compilation was forced on or off via `CARGO_INCREMENTAL`;
+//! /// `None` if it was left to the profile.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_INCREMENTAL`.
+//! pub static INCREMENTAL: Option<bool> = None;
 //!
 //! /// The features that were enabled during compilation.
 //! /// The feature-names will be normalized when using cargo < 1.85.
@@ -248,6 +310,9 @@
 //! pub static FEATURES_LOWERCASE: [&str; 0] = [];
 //! /// The feature-string as above, from lowercase strings.
 //! pub static FEATURES_LOWERCASE_STR: &str = "";
+//! /// The lowercase features as a space-separated string, with underscores written as
+//! /// hyphens like in `Cargo.toml`, e.g. for a version-banner.
+//! pub static FEATURES_BANNER: &str = "";
 //!
 //! /// The target architecture, given by `CARGO_CFG_TARGET_ARCH`.
 //! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_CFG_TARGET_ARCH`.
@@ -267,9 +332,66 @@
 //! /// The pointer width, given by `CARGO_CFG_TARGET_POINTER_WIDTH`.
 //! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_CFG_POINTER_WIDTH`.
 //! pub static CFG_POINTER_WIDTH: &str = "64";
+//! /// Whether `debug_assertions` were enabled, given by `CARGO_CFG_DEBUG_ASSERTIONS`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_DEBUG_ASSERTIONS`.
+//! pub static DEBUG_ASSERTIONS: bool = true;
+//! /// The panic-strategy, i.e. `unwind` or `abort`, given by `CARGO_CFG_PANIC`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_CFG_PANIC`.
+//! pub static CFG_PANIC: &str = "unwind";
+//! /// The widths of the atomic types supported by the target, given by
+//! /// `CARGO_CFG_TARGET_HAS_ATOMIC`, e.g. `8` or `ptr`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_CFG_TARGET_HAS_ATOMIC`.
+//! pub static CFG_TARGET_HAS_ATOMIC: [&str; 5] = ["8", "16", "32", "64", "ptr"];
+//!
+//! /// Every `CARGO_CFG_*`-variable set by cargo, as lowercase name without the prefix
+//! /// and value; the value is empty for cfgs without a value, e.g. `unix`.
+//! pub static CFG_ALL: [(&str, &str); 3] = [("panic", "unwind"), ("target_os", "linux"), ("unix", "")];
 //!
 //! /// The override-variables that were used during compilation.
 //! pub static OVERRIDE_VARIABLES_USED: [&str; 0] = [];
+//!
+//! /// A hash over `GIT_COMMIT_HASH`, `DEPENDENCIES`, `RUSTC_VERSION`, `TARGET` and
+//! /// `FEATURES`, as far as they were collected; it does not depend on the time of the build.
+//! /// The algorithm is chosen via `Options::set_hash_algorithm()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_BUILD_FINGERPRINT`.
+//! pub static BUILD_FINGERPRINT: &str = "85944171f73967e8";
+//! /// The release-channel or -stage of the build, e.g. `nightly` or `canary`.
+//! /// Only written if set via `Options::set_build_channel()` or
+//! /// `Options::set_build_channel_from_env()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_BUILD_CHANNEL`.
+//! pub static BUILD_CHANNEL: &str = "nightly";
+//! /// `true` if the build-script was run by an IDE, i.e. `rust-analyzer`, instead of a build.
+//! /// See `Options::set_skip_in_ide()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_BUILT_IN_IDE`.
+//! pub static BUILT_IN_IDE: bool = false;
+//! /// The operating system of the machine that built the crate, e.g. `linux` or `macos`.
+//! /// Only written if enabled via `Options::set_build_os()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_BUILD_OS`.
+//! pub static BUILD_OS: &str = "linux";
+//! /// The name and version of the operating system of the machine that built the crate;
+//! /// `None` if it could not be determined.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_BUILD_OS_VERSION`.
+//! pub static BUILD_OS_VERSION: Option<&str> = Some("Ubuntu 24.04.1 LTS");
+//! /// The CPU architecture of the machine that built the crate, e.g. `x86_64` or `aarch64`.
+//! /// Only written if enabled via `Options::set_build_os()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_BUILD_ARCH`.
+//! pub static BUILD_ARCH: &str = "x86_64";
+//! /// The number of CPUs available to `built` on the machine that built the crate;
+//! /// `1` if it could not be determined. This differs between machines and is
+//! /// therefore not reproducible.
+//! /// Only written if enabled via `Options::set_build_os()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_BUILD_CPU_COUNT`.
+//! pub static BUILD_CPU_COUNT: usize = 16;
+//! /// The time in milliseconds `built` took to collect this information; this does not
+//! /// include the rest of the build.
+//! /// Only written if enabled via `Options::set_collection_duration()`, and never if
+//! /// `SOURCE_DATE_EPOCH` is set.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_BUILT_COLLECTION_DURATION_MS`.
+//! pub static BUILT_COLLECTION_DURATION_MS: u64 = 12;
+//!
+//! /// The paths `built` registered via `cargo::rerun-if-changed`.
+//! /// Only written if enabled via `Options::set_rerun_if_changed()`.
+//! pub static BUILT_RERUN_PATHS: [&str; 2] = ["/home/user/project/.git/HEAD", "/home/user/project/.git/refs"];
 //! ```
 //!
 //! ### `cargo-lock`
@@ -297,8 +419,18 @@
 //! pub static DEPENDENCIES: [(&str, &str); 37] = [("autocfg", "1.0.0"), ("bitflags", "1.2.1"), ("built", "0.4.1"), ("cargo-lock", "4.0.1"), ("cc", "1.0.54"), ("cfg-if", "0.1.10"), ("chrono", "0.4.11"), ("example_project", "0.1.0"), ("git2", "0.13.6"), ("idna", "0.2.0"), ("jobserver", "0.1.21"), ("libc", "0.2.71"), ("libgit2-sys", "0.12.6+1.0.0"), ("libz-sys", "1.0.25"), ("log", "0.4.8"), ("matches", "0.1.8"), ("num-integer", "0.1.42"), ("num-traits", "0.2.11"), ("percent-encoding", "2.1.0"), ("pkg-config", "0.3.17"), ("proc-macro2", "1.0.17"), ("quote", "1.0.6"), ("semver", "1.0.0"), ("serde", "1.0.110"), ("serde_derive", "1.0.110"), ("smallvec", "1.4.0"), ("syn", "1.0.25"), ("time", "0.1.43"), ("toml", "0.5.6"), ("unicode-bidi", "0.3.4"), ("unicode-normalization", "0.1.12"), ("unicode-xid", "0.2.0"), ("url", "2.1.1"), ("vcpkg", "0.2.8"), ("winapi", "0.3.8"), ("winapi-i686-pc-windows-gnu", "0.4.0"), ("winapi-x86_64-pc-windows-gnu", "0.4.0")];
 //! /// The effective dependencies as a comma-separated string.
 //! pub static DEPENDENCIES_STR: &str = "autocfg 1.0.0, bitflags 1.2.1, built 0.4.1, cargo-lock 4.0.1, cc 1.0.54, cfg-if 0.1.10, chrono 0.4.11, example_project 0.1.0, git2 0.13.6, idna 0.2.0, jobserver 0.1.21, libc 0.2.71, libgit2-sys 0.12.6+1.0.0, libz-sys 1.0.25, log 0.4.8, matches 0.1.8, num-integer 0.1.42, num-traits 0.2.11, percent-encoding 2.1.0, pkg-config 0.3.17, proc-macro2 1.0.17, quote 1.0.6, semver 1.0.0, serde 1.0.110, serde_derive 1.0.110, smallvec 1.4.0, syn 1.0.25, time 0.1.43, toml 0.5.6, unicode-bidi 0.3.4, unicode-normalization 0.1.12, unicode-xid 0.2.0, url 2.1.1, vcpkg 0.2.8, winapi 0.3.8, winapi-i686-pc-windows-gnu 0.4.0, winapi-x86_64-pc-windows-gnu 0.4.0";
+//! /// The effective dependencies as `(name, version, source)`-tuples, where `source` is one of
+//! /// `registry`, `git` or `path`.
+//! pub static DEPENDENCIES_WITH_SOURCE: [(&str, &str, &str); 2] = [("built", "0.4.1", "path"), ("semver", "1.0.0", "registry")];
+//! /// Whether `DEPENDENCIES` was cut short via `Options::set_max_dependencies()`.
+//! pub static DEPENDENCIES_TRUNCATED: bool = false;
 //! ```
 //!
+//! If both `cargo-lock` and `cargo-metadata` are active, `Options::set_dependencies_resolved()`
+//! writes the dependencies Cargo resolved for the current target and the enabled features
+//! instead, which excludes build- and dev-dependencies as well as dependencies of other
+//! targets or of disabled features.
+//!
 //! ### `dependency-tree` (implies `cargo-lock`)
 //! Solve the dependency-graph in `Cargo.lock` to discern direct and indirect
 //! dependencies.
@@ -318,6 +450,40 @@
 //! pub static INDIRECT_DEPENDENCIES_STR: &str = r"android-tzdata 0.1.1, android_system_properties 0.1.5, autocfg 1.1.0, bitflags 2.4.0, bumpalo 3.13.0, cargo-lock 9.0.0, cc 1.0.83, cfg-if 1.0.0, chrono 0.4.29, core-foundation-sys 0.8.4, equivalent 1.0.1, example_project 0.1.0, fixedbitset 0.4.2, form_urlencoded 1.2.0, git2 0.18.0, hashbrown 0.14.0, iana-time-zone 0.1.57, iana-time-zone-haiku 0.1.2, idna 0.4.0, indexmap 2.0.0, jobserver 0.1.26, js-sys 0.3.64, libc 0.2.147, libgit2-sys 0.16.1+1.7.1, libz-sys 1.1.12, log 0.4.20, memchr 2.6.3, num-traits 0.2.16, once_cell 1.18.0, percent-encoding 2.3.0, petgraph 0.6.4, pkg-config 0.3.27, proc-macro2 1.0.66, quote 1.0.33, semver 1.0.18, serde 1.0.188, serde_derive 1.0.188, serde_spanned 0.6.3, syn 2.0.31, tinyvec 1.6.0, tinyvec_macros 0.1.1, toml 0.7.6, toml_datetime 0.6.3, toml_edit 0.19.14, unicode-bidi 0.3.13, unicode-ident 1.0.11, unicode-normalization 0.1.22, url 2.4.1, vcpkg 0.2.15, wasm-bindgen 0.2.87, wasm-bindgen-backend 0.2.87, wasm-bindgen-macro 0.2.87, wasm-bindgen-macro-support 0.2.87, wasm-bindgen-shared 0.2.87, windows 0.48.0, windows-targets 0.48.5, windows_aarch64_gnullvm 0.48.5, windows_aarch64_msvc 0.48.5, windows_i686_gnu 0.48.5, windows_i686_msvc 0.48.5, windows_x86_64_gnu 0.48.5, windows_x86_64_gnullvm 0.48.5, windows_x86_64_msvc 0.48.5, winnow 0.5.15";
 //! ```
 //!
+//! ### `cargo-metadata`
+//! Runs `cargo metadata` to retrieve information about the package that is not
+//! available from Cargo's environment variables.
+//!
+//! ```
+//! /// The Rust edition of the package, e.g. `2021`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_PKG_EDITION`.
+//! pub static PKG_EDITION: &str = "2021";
+//! /// The `[package.metadata]`-table of the package as JSON; `None` if it is empty.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_PKG_METADATA`.
+//! pub static PKG_METADATA: Option<&str> = Some(r#"{"myapp":{"key":"value"}}"#);
+//! /// The features declared by the package, sorted; `default` is only included if declared.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_FEATURES_AVAILABLE`.
+//! pub static FEATURES_AVAILABLE: [&str; 3] = ["default", "serde", "tls"];
+//! /// The `categories` of the package, in the order of the manifest.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_PKG_CATEGORIES`.
+//! pub static PKG_CATEGORIES: [&str; 1] = ["development-tools::build-utils"];
+//! /// The `keywords` of the package, in the order of the manifest.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_PKG_KEYWORDS`.
+//! pub static PKG_KEYWORDS: [&str; 2] = ["cargo", "build"];
+//! /// The native library the package links to, as declared by its `links`-key; `None` if there is none.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_PKG_LINKS`.
+//! pub static PKG_LINKS: Option<&str> = Some("git2");
+//! /// The binary `cargo run` runs by default, as declared by the `default-run`-key; `None` if there is none.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_PKG_DEFAULT_RUN`.
+//! pub static PKG_DEFAULT_RUN: Option<&str> = None;
+//! /// The path to the readme of the package, as declared by its `readme`-key or found by Cargo; `None` if there is none.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_PKG_README`.
+//! pub static PKG_README: Option<&str> = Some("README.md");
+//! /// The URL of the documentation of the package, as declared by its `documentation`-key; `None` if there is none.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_PKG_DOCUMENTATION`.
+//! pub static PKG_DOCUMENTATION: Option<&str> = Some("https://docs.rs/built");
+//! ```
+//!
 //! ### `git2`
 //! Try to open the git-repository at `manifest_location` and retrieve `HEAD`
 //! tag or commit id.
@@ -329,9 +495,16 @@
 //! result. `GIT_VERSION` and `GIT_DIRTY` will therefore always be `None` if
 //! a CI-platform is detected.
 //! ```
+//! /// `Some(true)` if a git-repository was found, even if it could not be read; `None`
+//! /// if the collection of git-information was skipped.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_AVAILABLE`.
+//! pub static GIT_AVAILABLE: Option<bool> = Some(true);
+//!
 //! /// If the crate was compiled from within a git-repository,
 //! /// `GIT_VERSION` contains HEAD's tag. The short commit id is used
-//! /// if HEAD is not tagged.
+//! /// if HEAD is not tagged. See `Options::set_git_describe_committish()` to
+//! /// describe another commit instead, and `Options::set_git_describe_all()`
+//! /// to consider branches as well.
 //! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_VERSION`.
 //! pub static GIT_VERSION: Option<&str> = Some("0.4.1-10-gca2af4f");
 //!
@@ -339,6 +512,32 @@
 //! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_DIRTY`.
 //! pub static GIT_DIRTY: Option<bool> = Some(true);
 //!
+//! /// If the index had unmerged paths, i.e. unresolved merge-conflicts. `None` if the
+//! /// repository is bare.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_HAS_CONFLICTS`.
+//! pub static GIT_HAS_CONFLICTS: Option<bool> = Some(false);
+//!
+//! /// The number of lines inserted in the working tree and the index, compared to
+//! /// HEAD. `None` if the repository is clean. Only written if enabled via
+//! /// `Options::set_git_dirty_lines()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_DIRTY_INSERTIONS`.
+//! pub static GIT_DIRTY_INSERTIONS: Option<usize> = Some(42);
+//!
+//! /// The number of lines deleted in the working tree and the index, compared to
+//! /// HEAD. `None` if the repository is clean. Only written if enabled via
+//! /// `Options::set_git_dirty_lines()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_DIRTY_DELETIONS`.
+//! pub static GIT_DIRTY_DELETIONS: Option<usize> = Some(7);
+//!
+//! /// The paths of the files in the working tree and the index that differ
+//! /// from HEAD, relative to the repository's root and sorted by name. Only
+//! /// written if enabled via `Options::set_git_dirty_files()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_DIRTY_FILES`.
+//! pub static GIT_DIRTY_FILES: [&str; 2] = ["src/lib.rs", "src/main.rs"];
+//!
+//! /// `true` if `GIT_DIRTY_FILES` was truncated to the maximum number of files.
+//! pub static GIT_DIRTY_FILES_TRUNCATED: bool = false;
+//!
 //! /// If the crate was compiled from within a git-repository,
 //! /// `GIT_HEAD_REF` contains full name to the reference pointed to by
 //! /// HEAD (e.g.: `refs/heads/master`). If HEAD is detached or the branch
@@ -347,7 +546,8 @@
 //! pub static GIT_HEAD_REF: Option<&str> = Some("refs/heads/master");
 //!
 //! /// If the crate was compiled from within a git-repository,
-//! /// `GIT_COMMIT_HASH` contains HEAD's full commit SHA-1 hash.
+//! /// `GIT_COMMIT_HASH` contains HEAD's full commit SHA-1 hash. See
+//! /// `Options::set_git_committish()` to use another commit instead.
 //! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_COMMIT_HASH`.
 //! pub static GIT_COMMIT_HASH: Option<&str> = Some("ca2af4f11bb8f4f6421c4cccf428bf4862573daf");
 //!
@@ -355,20 +555,168 @@
 //! /// `GIT_COMMIT_HASH_SHORT` contains HEAD's short commit SHA-1 hash.
 //! /// Can be overridden using `BUILT_OVERRIDE_{pkg_name}_GIT_COMMIT_HASH_SHORT`.
 //! pub static GIT_COMMIT_HASH_SHORT: Option<&str> = Some("ca2af4f");
+//!
+//! /// HEAD's full commit hash as raw bytes, as given by `GIT_COMMIT_HASH`.
+//! /// Only written if enabled via `Options::set_git_commit_hash_bytes()`.
+//! pub static GIT_COMMIT_HASH_BYTES: Option<[u8; 20]> = Some([
+//!     0xca, 0x2a, 0xf4, 0xf1, 0x1b, 0xb8, 0xf4, 0xf6,
+//!     0x42, 0x1c, 0x4c, 0xcc, 0xf4, 0x28, 0xbf, 0x48,
+//!     0x62, 0x57, 0x3d, 0xaf,
+//! ]);
+//!
+//! /// If the crate was compiled from within a git-repository,
+//! /// `GIT_OBJECT_FORMAT` contains the hash-algorithm of the repository's
+//! /// objects, i.e. `sha1` or `sha256`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_OBJECT_FORMAT`.
+//! pub static GIT_OBJECT_FORMAT: Option<&str> = Some("sha1");
+//!
+//! /// If the crate was compiled from within a git-repository,
+//! /// `GIT_COMMIT_ON_REMOTE` is `Some(true)` if HEAD's commit is reachable from
+//! /// any remote-tracking branch. `None` if there are no remote-tracking branches.
+//! /// Only written if enabled via `Options::set_git_commit_on_remote()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_COMMIT_ON_REMOTE`.
+//! pub static GIT_COMMIT_ON_REMOTE: Option<bool> = Some(true);
+//!
+//! /// `Some(true)` if HEAD's commit is reachable from the base branch given to
+//! /// `Options::set_git_base_branch()`. `None` if there is no such branch.
+//! /// Only written if enabled via `Options::set_git_base_branch()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_IS_ANCESTOR_OF_BASE`.
+//! pub static GIT_IS_ANCESTOR_OF_BASE: Option<bool> = Some(true);
+//!
+//! /// If HEAD's commit is signed, `GIT_COMMIT_SIGNING_KEY` contains the signing key's
+//! /// fingerprint (or key id) for OpenPGP-signatures, or the public key for SSH-signatures.
+//! /// The signature is not verified.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_COMMIT_SIGNING_KEY`.
+//! pub static GIT_COMMIT_SIGNING_KEY: Option<&str> = Some("225BD19B2836F8169943471636D1D2463C4B7BE8");
+//! /// Whether the signature of HEAD's commit was verified by `git verify-commit` against
+//! /// the keyring of the build-machine; `Some(false)` if the signature is bad. `None` if the
+//! /// commit is not signed or the signature could not be checked.
+//! /// Only available if the `verify-signature`-feature is enabled as well; see
+//! /// `Options::set_git_verify_commit_signature()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_COMMIT_SIGNATURE_VERIFIED`.
+//! pub static GIT_COMMIT_SIGNATURE_VERIFIED: Option<bool> = Some(true);
+//! /// The note attached to HEAD's commit, as shown by `git notes show`. `None` if there is
+//! /// no note or the notes were not fetched.
+//! /// Only written if enabled via `Options::set_git_commit_notes()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_COMMIT_NOTES`.
+//! pub static GIT_COMMIT_NOTES: Option<&str> = Some("Reviewed-by: Alice");
+//!
+//! /// The number of unique author-emails in the history reachable from HEAD.
+//! /// `None` if the repository is a shallow clone.
+//! /// Only written if enabled via `Options::set_git_contributor_count()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_CONTRIBUTOR_COUNT`.
+//! pub static GIT_CONTRIBUTOR_COUNT: Option<usize> = Some(12);
+//! /// The number of commits in the history reachable from HEAD.
+//! /// `None` if the repository is a shallow clone.
+//! /// Only written if enabled via `Options::set_git_commits_count()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_COMMITS_COUNT`.
+//! pub static GIT_COMMITS_COUNT: Option<usize> = Some(1234);
+//! /// The number of commits on the first-parent chain from HEAD back to the root commit.
+//! /// `None` if the repository is a shallow clone.
+//! /// Only written if enabled via `Options::set_git_commit_depth()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_COMMIT_DEPTH`.
+//! pub static GIT_COMMIT_DEPTH: Option<usize> = Some(987);
+//! /// The number of files changed by HEAD's commit compared to its first parent.
+//! /// `None` if HEAD's commit has no parent.
+//! /// Only written if enabled via `Options::set_git_commit_files_changed()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_COMMIT_FILES_CHANGED`.
+//! pub static GIT_COMMIT_FILES_CHANGED: Option<usize> = Some(3);
+//!
+//! /// The fetch-URL of the remote named `origin`, with any credentials removed.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_REMOTE_ORIGIN_URL`.
+//! pub static GIT_REMOTE_ORIGIN_URL: Option<&str> = Some("https://github.com/lukaslueg/built.git");
+//!
+//! /// The hosting provider of the remote named `origin`, i.e. `github`, `gitlab` or
+//! /// `bitbucket`, as determined by `GIT_REMOTE_ORIGIN_URL`. `None` if the provider is not
+//! /// recognized.
+//! pub static GIT_HOSTING_PROVIDER: Option<&str> = Some("github");
+//!
+//! /// A link to `GIT_COMMIT_HASH` on the website of `GIT_HOSTING_PROVIDER`, e.g.
+//! /// `https://github.com/owner/repo/commit/<hash>`. Overrides of those apply. `None` if
+//! /// the provider is not recognized.
+//! pub static GIT_COMMIT_WEB_URL: Option<&str> = Some("https://github.com/lukaslueg/built/commit/66a6e8b1c0b7dc2ad7b1ef3b9f1ff7c4a1c5b8a2");
+//!
+//! /// The name of the remote the current branch tracks, e.g. `origin`. `None` if HEAD
+//! /// is detached or the branch does not track a remote.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_UPSTREAM_REMOTE`.
+//! pub static GIT_UPSTREAM_REMOTE: Option<&str> = Some("origin");
+//!
+//! /// The names of all tags pointing at HEAD's commit, sorted by name.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_TAGS_AT_HEAD`.
+//! pub static GIT_TAGS_AT_HEAD: [&str; 1] = ["0.8.0"];
+//!
+//! /// The names of the tags pointing at HEAD's commit or any of its ancestors, sorted
+//! /// by the date of their commits, the newest first, and by name if those are equal.
+//! /// Empty if the repository is a shallow clone.
+//! /// Only written if enabled via `Options::set_git_reachable_tags()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_REACHABLE_TAGS`.
+//! pub static GIT_REACHABLE_TAGS: [&str; 3] = ["0.8.0", "0.7.0", "0.6.1"];
+//!
+//! /// The message of the annotated tag pointing at HEAD's commit, e.g. release-notes.
+//! /// If there are multiple, the first one by name is used. `None` if HEAD's commit
+//! /// has no annotated tag.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_TAG_MESSAGE`.
+//! pub static GIT_TAG_MESSAGE: Option<&str> = Some("Release 0.8.0\n");
+//!
+//! /// `Some(true)` if HEAD's commit is tagged and the repository is not dirty, as
+//! /// determined by `GIT_TAGS_AT_HEAD` and `GIT_DIRTY`. Overrides of those apply.
+//! pub static GIT_IS_TAGGED_RELEASE: Option<bool> = Some(true);
+//!
+//! /// The commit-date of HEAD's commit, by default in RFC2822 and in the committer's timezone-offset.
+//! /// Only available if the `chrono`-feature is enabled as well; see `Options::set_git_time_format()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_COMMIT_DATE`.
+//! pub static GIT_COMMIT_DATE: Option<&str> = Some("Fri, 1 Mar 2024 18:04:56 +0530");
+//!
+//! /// The operation in progress in the repository, e.g. `merge`, `rebase` or `cherry-pick`; `clean` if there is none.
+//! /// Only written if the crate was compiled from within a git-repository.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_STATE`.
+//! pub static GIT_STATE: &str = "clean";
+//!
+//! /// The number of local and remote-tracking branches in the repository.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_BRANCH_COUNT`.
+//! pub static GIT_BRANCH_COUNT: Option<usize> = Some(4);
+//!
+//! /// The number of tags in the repository.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_TAG_COUNT`.
+//! pub static GIT_TAG_COUNT: Option<usize> = Some(27);
+//!
+//! /// A hash over the paths, modes and contents of all files in HEAD's tree, which
+//! /// does not change with the commit-message or branch-name. `None` in bare
+//! /// repositories and shallow clones. Only written if enabled via
+//! /// `Options::set_source_tree_hash()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_SOURCE_TREE_HASH`.
+//! pub static SOURCE_TREE_HASH: Option<&str> = Some("8f32a3c1e0b5d47a");
+//!
+//! /// The name of the most recent tag reachable from HEAD, like `git describe --tags --abbrev=0`; `None` if there is none.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_NEAREST_TAG`.
+//! pub static GIT_NEAREST_TAG: Option<&str> = Some("0.8.0");
+//!
+//! /// The full hash of the commit `GIT_NEAREST_TAG` points to; `None` if there is no tag.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_NEAREST_TAG_COMMIT`.
+//! pub static GIT_NEAREST_TAG_COMMIT: Option<&str> = Some("7a8e8f4e4b4f1a3bbfb1cf1b3b6a2f8b86dbd25a");
 //! ```
 //!
 //! ### `gix`
 //! An alternative to `git2` that uses the `gix` (gitoxide) crate for git operations.
 //! This feature provides the same git information as `git2` but uses a pure Rust
-//! implementation with potentially faster build times.
+//! implementation with potentially faster build times. All information, including
+//! `GIT_DIRTY`, is collected by `gix` itself: `--no-default-features --features gix`
+//! does not link `libgit2` and does not require a C-toolchain, e.g. when cross-compiling.
 //!
 //! **Note**: When both `git2` and `gix` features are enabled, `git2` takes precedence.
 //!
 //! This feature produces the same output format as `git2`:
 //! ```
+//! /// `Some(true)` if a git-repository was found, even if it could not be read; `None`
+//! /// if the collection of git-information was skipped.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_AVAILABLE`.
+//! pub static GIT_AVAILABLE: Option<bool> = Some(true);
+//!
 //! /// If the crate was compiled from within a git-repository,
 //! /// `GIT_VERSION` contains HEAD's tag. The short commit id is used
-//! /// if HEAD is not tagged.
+//! /// if HEAD is not tagged. See `Options::set_git_describe_committish()` to
+//! /// describe another commit instead, and `Options::set_git_describe_all()`
+//! /// to consider branches as well.
 //! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_VERSION`.
 //! pub static GIT_VERSION: Option<&str> = Some("0.4.1-10-gca2af4f");
 //!
@@ -376,6 +724,32 @@
 //! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_DIRTY`.
 //! pub static GIT_DIRTY: Option<bool> = Some(true);
 //!
+//! /// If the index had unmerged paths, i.e. unresolved merge-conflicts. `None` if the
+//! /// repository is bare.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_HAS_CONFLICTS`.
+//! pub static GIT_HAS_CONFLICTS: Option<bool> = Some(false);
+//!
+//! /// The number of lines inserted in the working tree and the index, compared to
+//! /// HEAD. `None` if the repository is clean. Only written if enabled via
+//! /// `Options::set_git_dirty_lines()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_DIRTY_INSERTIONS`.
+//! pub static GIT_DIRTY_INSERTIONS: Option<usize> = Some(42);
+//!
+//! /// The number of lines deleted in the working tree and the index, compared to
+//! /// HEAD. `None` if the repository is clean. Only written if enabled via
+//! /// `Options::set_git_dirty_lines()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_DIRTY_DELETIONS`.
+//! pub static GIT_DIRTY_DELETIONS: Option<usize> = Some(7);
+//!
+//! /// The paths of the files in the working tree and the index that differ
+//! /// from HEAD, relative to the repository's root and sorted by name. Only
+//! /// written if enabled via `Options::set_git_dirty_files()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_DIRTY_FILES`.
+//! pub static GIT_DIRTY_FILES: [&str; 2] = ["src/lib.rs", "src/main.rs"];
+//!
+//! /// `true` if `GIT_DIRTY_FILES` was truncated to the maximum number of files.
+//! pub static GIT_DIRTY_FILES_TRUNCATED: bool = false;
+//!
 //! /// If the crate was compiled from within a git-repository,
 //! /// `GIT_HEAD_REF` contains full name to the reference pointed to by
 //! /// HEAD (e.g.: `refs/heads/master`). If HEAD is detached or the branch
@@ -384,7 +758,8 @@
 //! pub static GIT_HEAD_REF: Option<&str> = Some("refs/heads/master");
 //!
 //! /// If the crate was compiled from within a git-repository,
-//! /// `GIT_COMMIT_HASH` contains HEAD's full commit SHA-1 hash.
+//! /// `GIT_COMMIT_HASH` contains HEAD's full commit SHA-1 hash. See
+//! /// `Options::set_git_committish()` to use another commit instead.
 //! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_COMMIT_HASH`.
 //! pub static GIT_COMMIT_HASH: Option<&str> = Some("ca2af4f11bb8f4f6421c4cccf428bf4862573daf");
 //!
@@ -392,8 +767,154 @@
 //! /// `GIT_COMMIT_HASH_SHORT` contains HEAD's short commit SHA-1 hash.
 //! /// Can be overridden using `BUILT_OVERRIDE_{pkg_name}_GIT_COMMIT_HASH_SHORT`.
 //! pub static GIT_COMMIT_HASH_SHORT: Option<&str> = Some("ca2af4f");
+//!
+//! /// HEAD's full commit hash as raw bytes, as given by `GIT_COMMIT_HASH`.
+//! /// Only written if enabled via `Options::set_git_commit_hash_bytes()`.
+//! pub static GIT_COMMIT_HASH_BYTES: Option<[u8; 20]> = Some([
+//!     0xca, 0x2a, 0xf4, 0xf1, 0x1b, 0xb8, 0xf4, 0xf6,
+//!     0x42, 0x1c, 0x4c, 0xcc, 0xf4, 0x28, 0xbf, 0x48,
+//!     0x62, 0x57, 0x3d, 0xaf,
+//! ]);
+//!
+//! /// If the crate was compiled from within a git-repository,
+//! /// `GIT_OBJECT_FORMAT` contains the hash-algorithm of the repository's
+//! /// objects, i.e. `sha1` or `sha256`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_OBJECT_FORMAT`.
+//! pub static GIT_OBJECT_FORMAT: Option<&str> = Some("sha1");
+//!
+//! /// If the crate was compiled from within a git-repository,
+//! /// `GIT_COMMIT_ON_REMOTE` is `Some(true)` if HEAD's commit is reachable from
+//! /// any remote-tracking branch. `None` if there are no remote-tracking branches.
+//! /// Only written if enabled via `Options::set_git_commit_on_remote()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_COMMIT_ON_REMOTE`.
+//! pub static GIT_COMMIT_ON_REMOTE: Option<bool> = Some(true);
+//!
+//! /// `Some(true)` if HEAD's commit is reachable from the base branch given to
+//! /// `Options::set_git_base_branch()`. `None` if there is no such branch.
+//! /// Only written if enabled via `Options::set_git_base_branch()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_IS_ANCESTOR_OF_BASE`.
+//! pub static GIT_IS_ANCESTOR_OF_BASE: Option<bool> = Some(true);
+//!
+//! /// If HEAD's commit is signed, `GIT_COMMIT_SIGNING_KEY` contains the signing key's
+//! /// fingerprint (or key id) for OpenPGP-signatures, or the public key for SSH-signatures.
+//! /// The signature is not verified.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_COMMIT_SIGNING_KEY`.
+//! pub static GIT_COMMIT_SIGNING_KEY: Option<&str> = Some("225BD19B2836F8169943471636D1D2463C4B7BE8");
+//! /// Whether the signature of HEAD's commit was verified by `git verify-commit` against
+//! /// the keyring of the build-machine; `Some(false)` if the signature is bad. `None` if the
+//! /// commit is not signed or the signature could not be checked.
+//! /// Only available if the `verify-signature`-feature is enabled as well; see
+//! /// `Options::set_git_verify_commit_signature()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_COMMIT_SIGNATURE_VERIFIED`.
+//! pub static GIT_COMMIT_SIGNATURE_VERIFIED: Option<bool> = Some(true);
+//! /// The note attached to HEAD's commit, as shown by `git notes show`. `None` if there is
+//! /// no note or the notes were not fetched.
+//! /// Only written if enabled via `Options::set_git_commit_notes()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_COMMIT_NOTES`.
+//! pub static GIT_COMMIT_NOTES: Option<&str> = Some("Reviewed-by: Alice");
+//!
+//! /// The number of unique author-emails in the history reachable from HEAD.
+//! /// `None` if the repository is a shallow clone.
+//! /// Only written if enabled via `Options::set_git_contributor_count()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_CONTRIBUTOR_COUNT`.
+//! pub static GIT_CONTRIBUTOR_COUNT: Option<usize> = Some(12);
+//! /// The number of commits in the history reachable from HEAD.
+//! /// `None` if the repository is a shallow clone.
+//! /// Only written if enabled via `Options::set_git_commits_count()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_COMMITS_COUNT`.
+//! pub static GIT_COMMITS_COUNT: Option<usize> = Some(1234);
+//! /// The number of commits on the first-parent chain from HEAD back to the root commit.
+//! /// `None` if the repository is a shallow clone.
+//! /// Only written if enabled via `Options::set_git_commit_depth()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_COMMIT_DEPTH`.
+//! pub static GIT_COMMIT_DEPTH: Option<usize> = Some(987);
+//! /// The number of files changed by HEAD's commit compared to its first parent.
+//! /// `None` if HEAD's commit has no parent.
+//! /// Only written if enabled via `Options::set_git_commit_files_changed()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_COMMIT_FILES_CHANGED`.
+//! pub static GIT_COMMIT_FILES_CHANGED: Option<usize> = Some(3);
+//!
+//! /// The fetch-URL of the remote named `origin`, with any credentials removed.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_REMOTE_ORIGIN_URL`.
+//! pub static GIT_REMOTE_ORIGIN_URL: Option<&str> = Some("https://github.com/lukaslueg/built.git");
+//!
+//! /// The hosting provider of the remote named `origin`, i.e. `github`, `gitlab` or
+//! /// `bitbucket`, as determined by `GIT_REMOTE_ORIGIN_URL`. `None` if the provider is not
+//! /// recognized.
+//! pub static GIT_HOSTING_PROVIDER: Option<&str> = Some("github");
+//!
+//! /// A link to `GIT_COMMIT_HASH` on the website of `GIT_HOSTING_PROVIDER`, e.g.
+//! /// `https://github.com/owner/repo/commit/<hash>`. Overrides of those apply. `None` if
+//! /// the provider is not recognized.
+//! pub static GIT_COMMIT_WEB_URL: Option<&str> = Some("https://github.com/lukaslueg/built/commit/66a6e8b1c0b7dc2ad7b1ef3b9f1ff7c4a1c5b8a2");
+//!
+//! /// The name of the remote the current branch tracks, e.g. `origin`. `None` if HEAD
+//! /// is detached or the branch does not track a remote.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_UPSTREAM_REMOTE`.
+//! pub static GIT_UPSTREAM_REMOTE: Option<&str> = Some("origin");
+//!
+//! /// The names of all tags pointing at HEAD's commit, sorted by name.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_TAGS_AT_HEAD`.
+//! pub static GIT_TAGS_AT_HEAD: [&str; 1] = ["0.8.0"];
+//!
+//! /// The names of the tags pointing at HEAD's commit or any of its ancestors, sorted
+//! /// by the date of their commits, the newest first, and by name if those are equal.
+//! /// Empty if the repository is a shallow clone.
+//! /// Only written if enabled via `Options::set_git_reachable_tags()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_REACHABLE_TAGS`.
+//! pub static GIT_REACHABLE_TAGS: [&str; 3] = ["0.8.0", "0.7.0", "0.6.1"];
+//!
+//! /// The message of the annotated tag pointing at HEAD's commit, e.g. release-notes.
+//! /// If there are multiple, the first one by name is used. `None` if HEAD's commit
+//! /// has no annotated tag.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_TAG_MESSAGE`.
+//! pub static GIT_TAG_MESSAGE: Option<&str> = Some("Release 0.8.0\n");
+//!
+//! /// `Some(true)` if HEAD's commit is tagged and the repository is not dirty, as
+//! /// determined by `GIT_TAGS_AT_HEAD` and `GIT_DIRTY`. Overrides of those apply.
+//! pub static GIT_IS_TAGGED_RELEASE: Option<bool> = Some(true);
+//!
+//! /// The commit-date of HEAD's commit, by default in RFC2822 and in the committer's timezone-offset.
+//! /// Only available if the `chrono`-feature is enabled as well; see `Options::set_git_time_format()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_COMMIT_DATE`.
+//! pub static GIT_COMMIT_DATE: Option<&str> = Some("Fri, 1 Mar 2024 18:04:56 +0530");
+//!
+//! /// The operation in progress in the repository, e.g. `merge`, `rebase` or `cherry-pick`; `clean` if there is none.
+//! /// Only written if the crate was compiled from within a git-repository.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_STATE`.
+//! pub static GIT_STATE: &str = "clean";
+//!
+//! /// The number of local and remote-tracking branches in the repository.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_BRANCH_COUNT`.
+//! pub static GIT_BRANCH_COUNT: Option<usize> = Some(4);
+//!
+//! /// The number of tags in the repository.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_TAG_COUNT`.
+//! pub static GIT_TAG_COUNT: Option<usize> = Some(27);
+//!
+//! /// A hash over the paths, modes and contents of all files in HEAD's tree, which
+//! /// does not change with the commit-message or branch-name. `None` in bare
+//! /// repositories and shallow clones. Only written if enabled via
+//! /// `Options::set_source_tree_hash()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_SOURCE_TREE_HASH`.
+//! pub static SOURCE_TREE_HASH: Option<&str> = Some("8f32a3c1e0b5d47a");
+//!
+//! /// The name of the most recent tag reachable from HEAD, like `git describe --tags --abbrev=0`; `None` if there is none.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_NEAREST_TAG`.
+//! pub static GIT_NEAREST_TAG: Option<&str> = Some("0.8.0");
+//!
+//! /// The full hash of the commit `GIT_NEAREST_TAG` points to; `None` if there is no tag.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_GIT_NEAREST_TAG_COMMIT`.
+//! pub static GIT_NEAREST_TAG_COMMIT: Option<&str> = Some("7a8e8f4e4b4f1a3bbfb1cf1b3b6a2f8b86dbd25a");
 //! ```
 //!
+//! ### `sha2`, `sha1`, `blake3`
+//!
+//! Make `HashAlgorithm::Sha256`, `HashAlgorithm::Sha1` and `HashAlgorithm::Blake3` available
+//! to `Options::set_hash_algorithm()`, respectively. Hashes like `BUILD_FINGERPRINT` use
+//! SHA-256 by default if `sha2` is enabled, and FNV-1a otherwise, which requires no
+//! dependencies.
+//!
 //! ### `chrono`
 //!
 //! The build-time is recorded as `BUILT_TIME_UTC`. If `built` is included as a runtime-dependency,
@@ -409,7 +930,37 @@
 //! /// Can be overridden with`BUILT_OVERRIDE_BUILT_TIME_UTC`; the override takes precedence
 //! /// over `SOURCE_DATE_EPOCH`; it *must* parse via `chrono::DateTime::parse_from_rfc2822()`.
 //! pub static BUILT_TIME_UTC: &str = "Wed, 27 May 2020 18:12:39 +0000";
+//! /// The build time in RFC2822, in the local timezone of the build-machine; UTC if
+//! /// `SOURCE_DATE_EPOCH` is set, `None` if the timezone can't be determined.
+//! /// Only written if enabled via `Options::set_time_local()`.
+//! /// Can be overridden with `BUILT_OVERRIDE_{pkg_name}_BUILT_TIME_LOCAL`.
+//! pub static BUILT_TIME_LOCAL: Option<&str> = Some("Wed, 27 May 2020 20:12:39 +0200");
+//! /// The year of the build time, UTC.
+//! /// Only written if enabled via `Options::set_time_components()`.
+//! pub static BUILT_YEAR: i32 = 2020;
+//! /// The month of the build time, UTC, starting at `1`.
+//! /// Only written if enabled via `Options::set_time_components()`.
+//! pub static BUILT_MONTH: u32 = 5;
+//! /// The day of the month of the build time, UTC, starting at `1`.
+//! /// Only written if enabled via `Options::set_time_components()`.
+//! pub static BUILT_DAY: u32 = 27;
+//! /// The ISO 8601 week-number of the build time, UTC, starting at `1`. Notice that
+//! /// around new year, the week may belong to the previous or the next year.
+//! /// Only written if enabled via `Options::set_time_components()`.
+//! pub static BUILT_ISO_WEEK: u32 = 22;
 //! ```
+//!
+//! ### `tokio`
+//!
+//! Makes `Options::collect_map_async()` available, which collects the information on
+//! `tokio`'s pool for blocking tasks. This is for tools which use `built` as a library;
+//! build-scripts have no use for it.
+//!
+//! ### `verify-signature`
+//!
+//! Makes `Options::set_git_verify_commit_signature()` available, which verifies the signature
+//! of HEAD's commit as `GIT_COMMIT_SIGNATURE_VERIFIED` by running `git verify-commit`. This
+//! requires `git2` or `gix`, and `git` as well as e.g. `gpg` on the build-machine.
 
 #[cfg(feature = "cargo-lock")]
 mod dependencies;
@@ -422,9 +973,12 @@ mod git_shared;
 mod gix;
 #[cfg(feature = "chrono")]
 mod krono;
+#[cfg(feature = "cargo-metadata")]
+mod metadata;
+mod options;
 pub mod util;
 
-use std::{env, fmt, fs, io, io::Write, path};
+use std::{collections, env, fmt, io, path};
 
 #[cfg(feature = "semver")]
 pub use semver;
@@ -433,6 +987,11 @@ pub use semver;
 pub use chrono;
 
 pub use environment::CIPlatform;
+#[cfg(any(feature = "git2", feature = "gix"))]
+pub use options::DirtyStatuses;
+#[cfg(all(feature = "chrono", any(feature = "git2", feature = "gix")))]
+pub use options::TimeFormat;
+pub use options::{HashAlgorithm, Options};
 
 #[doc = include_str!("../README.md")]
 #[allow(dead_code)]
@@ -447,11 +1006,14 @@ const SOURCE_DATE_EPOCH: &str = "SOURCE_DATE_EPOCH";
 
 macro_rules! write_variable {
     ($writer:expr, $name:expr, $datatype:expr, $value:expr, $doc:expr) => {
-        writeln!(
-            $writer,
-            "#[allow(clippy::needless_raw_string_hashes)]\n#[doc=r#\"{}\"#]\n#[allow(dead_code)]\npub static {}: {} = {};",
-            $doc, $name, $datatype, $value
-        )?;
+        if $writer.emits($name) {
+            $writer.write_variable(
+                $name,
+                format_args!("{}", $datatype),
+                format_args!("{}", $value),
+                format_args!("{}", $doc),
+            )?;
+        }
     };
 }
 pub(crate) use write_variable;
@@ -476,78 +1038,680 @@ pub(crate) fn fmt_option_str<S: fmt::Display>(o: Option<S>) -> String {
     }
 }
 
-/// Writes rust-code describing the crate at `manifest_location` to a new file named `dst`.
+pub(crate) fn fmt_option<T: fmt::Display>(o: Option<T>) -> String {
+    match o {
+        Some(v) => format!("Some({v})"),
+        None => "None".to_owned(),
+    }
+}
+
+/// A variable as collected, before it is written in any format.
+#[derive(Debug, Clone)]
+struct Variable {
+    name: String,
+    datatype: String,
+    /// The value as a Rust-literal of type `datatype`.
+    value: String,
+    doc: String,
+}
+
+/// The variables whose values make up `BUILD_FINGERPRINT`.
+const FINGERPRINT_INPUTS: [&str; 5] = [
+    "GIT_COMMIT_HASH",
+    "DEPENDENCIES",
+    "RUSTC_VERSION",
+    "TARGET",
+    "FEATURES",
+];
+
+/// Collects the variables selected by `Options`, to be written in some format.
+pub(crate) struct BuiltWriter<'a> {
+    options: &'a Options,
+    variables: Vec<Variable>,
+    /// The names of `variables`, to detect variables that are written more than once.
+    names: collections::HashSet<String>,
+    rerun_paths: Vec<path::PathBuf>,
+    /// The values of the variables that identify the build's inputs, as first collected and
+    /// before any formatting-option like `Options::set_unwrap_known_values()` applies.
+    inputs: Vec<u8>,
+    /// Whether `cargo::`-directives are printed, i.e. if the variables are collected for a
+    /// build-script.
+    directives: bool,
+    /// Prepended to the name of every variable, e.g. `APP_` for a named git-source.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    prefix: String,
+}
+
+impl<'a> BuiltWriter<'a> {
+    pub(crate) fn new(options: &'a Options) -> Self {
+        // The items `write_rust()` generates besides the variables occupy their names, too
+        let names = [
+            (options.emit_map, "BUILT_MAP"),
+            (options.emit_print_fn, "print_build_info"),
+            (options.emit_macro, "build_info"),
+        ]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, name)| name.to_owned())
+        .collect();
+        Self {
+            options,
+            variables: Vec::new(),
+            names,
+            rerun_paths: Vec::new(),
+            inputs: Vec::new(),
+            directives: true,
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            prefix: String::new(),
+        }
+    }
+
+    /// Like `BuiltWriter::new()`, but for collecting the variables outside of a build-script,
+    /// e.g. into a map, so no `cargo::`-directives are printed.
+    pub(crate) fn quiet(options: &'a Options) -> Self {
+        Self {
+            directives: false,
+            ..Self::new(options)
+        }
+    }
+
+    /// Prints the `cargo::`-directive `directive`, e.g. `warning=...`, unless this is `quiet()`.
+    pub(crate) fn directive(&self, directive: fmt::Arguments<'_>) {
+        if self.directives {
+            println!("cargo::{directive}");
+        }
+    }
+
+    /// Whether the variable `name` is written at all.
+    pub(crate) fn emits(&self, name: &str) -> bool {
+        self.options.emits(&self.prefixed(name))
+    }
+
+    /// The name of the variable `name`, as written.
+    fn prefixed(&self, name: &str) -> String {
+        #[cfg(any(feature = "git2", feature = "gix"))]
+        return format!("{}{name}", self.prefix);
+        #[cfg(not(any(feature = "git2", feature = "gix")))]
+        name.to_owned()
+    }
+
+    /// Records `path` as an input that the collected information depends on.
+    pub(crate) fn rerun_if_changed(&mut self, path: path::PathBuf) {
+        self.rerun_paths.push(path);
+    }
+
+    /// Records the variable `name`, whose `value` is given as a Rust-literal of type `datatype`.
+    pub(crate) fn write_variable(
+        &mut self,
+        name: &str,
+        datatype: fmt::Arguments<'_>,
+        value: fmt::Arguments<'_>,
+        doc: fmt::Arguments<'_>,
+    ) -> io::Result<()> {
+        let name = self.prefixed(name);
+        if !self.names.insert(name.clone()) {
+            return Err(io::Error::other(format!(
+                "The variable `{name}` is written more than once, e.g. via \
+                `Options::add_variable()` or `Options::add_named_git_source()`"
+            )));
+        }
+        let mut datatype = datatype.to_string();
+        let mut value = value.to_string();
+        if FINGERPRINT_INPUTS.contains(&name.as_str()) {
+            self.inputs.extend_from_slice(name.as_bytes());
+            self.inputs.push(b'=');
+            self.inputs.extend_from_slice(value.as_bytes());
+            self.inputs.push(b'\n');
+        }
+        if self.options.unwrap_known_values {
+            if let (Some(inner_type), Some(inner_value)) = (
+                datatype
+                    .strip_prefix("Option<")
+                    .and_then(|t| t.strip_suffix('>')),
+                value
+                    .strip_prefix("Some(")
+                    .and_then(|v| v.strip_suffix(')')),
+            ) {
+                (datatype, value) = (inner_type.to_owned(), inner_value.to_owned());
+            }
+        }
+        self.variables.push(Variable {
+            name,
+            datatype,
+            value,
+            doc: doc.to_string(),
+        });
+        Ok(())
+    }
+
+    /// A hash over the values of the variables that identify the build's inputs, as far as
+    /// they were collected.
+    fn fingerprint(&self) -> String {
+        self.options.hash_algorithm.hex_digest(&self.inputs)
+    }
+
+    /// Writes the collected variables as Rust-code, to be `include!()`ed.
+    ///
+    /// Lines always end in `\n`, regardless of the platform or how this file was checked out,
+    /// so the output is byte-identical across platforms.
+    fn write_rust(&self, w: &mut impl io::Write) -> io::Result<()> {
+        w.write_all(
+            b"//\n// EVERYTHING BELOW THIS POINT WAS AUTO-GENERATED DURING COMPILATION. DO NOT MODIFY.\n//\n",
+        )?;
+
+        // String-literals that appear more than once are emitted as `const`s once, and
+        // referenced by name everywhere else.
+        let mut interned = collections::HashMap::new();
+        if self.options.intern_strings {
+            let mut counts = collections::HashMap::<String, usize>::new();
+            for var in &self.variables {
+                util::replace_str_literals(&var.value, |literal| {
+                    *counts.entry(literal.to_owned()).or_default() += 1;
+                    None
+                });
+            }
+            let mut repeated = counts
+                .into_iter()
+                .filter(|(literal, count)| *count > 1 && literal.len() > 2)
+                .map(|(literal, _)| literal)
+                .collect::<Vec<_>>();
+            repeated.sort_unstable();
+            for (i, literal) in repeated.into_iter().enumerate() {
+                let name = format!("__BUILT_STR_{i}");
+                writeln!(
+                    w,
+                    "#[doc(hidden)]\n#[allow(dead_code)]\nconst {name}: &str = {literal};"
+                )?;
+                interned.insert(literal, name);
+            }
+        }
+
+        for Variable {
+            name,
+            datatype,
+            value,
+            doc,
+        } in &self.variables
+        {
+            let value = util::replace_str_literals(value, |literal| interned.get(literal).cloned());
+            let (datatype, value) = match util::cow_str(datatype, &value) {
+                Some((datatype, value)) if self.options.cow_strings => (datatype.into(), value),
+                _ => (std::borrow::Cow::Borrowed(datatype.as_str()), value),
+            };
+            if self.options.compact {
+                writeln!(
+                    w,
+                    "#[allow(dead_code)]\npub static {name}: {datatype} = {value};"
+                )?;
+                continue;
+            }
+            // A bare `\r` is not allowed in the raw string-literal
+            let doc = doc.replace("\r\n", "\n").replace('\r', "\n");
+            writeln!(
+                w,
+                "#[allow(clippy::needless_raw_string_hashes)]\n#[doc=r#\"{doc}\"#]\n#[allow(dead_code)]\npub static {name}: {datatype} = {value};",
+            )?;
+        }
+
+        let (doc, indent) = if self.options.compact {
+            (false, "")
+        } else {
+            (true, "    ")
+        };
+
+        if self.options.emit_map {
+            let entries = self
+                .variables
+                .iter()
+                .filter_map(|var| Some((&var.name, util::plain_value(&var.value)?)))
+                .collect::<Vec<_>>();
+            if doc {
+                w.write_all(b"/// The name and plain value of every variable in this file; variables whose value is `None` are left out.\n")?;
+            }
+            writeln!(
+                w,
+                "#[allow(dead_code)]\npub static BUILT_MAP: &[(&str, &str)] = &{};",
+                util::ArrayDisplay(&entries, |(name, value), f| write!(
+                    f,
+                    "(\"{name}\", \"{}\")",
+                    value.escape_default()
+                ))
+            )?;
+        }
+
+        if self.options.emit_print_fn {
+            if doc {
+                w.write_all(
+                    b"/// Prints every variable in this file as `NAME: value`-lines to stdout.\n",
+                )?;
+            }
+            w.write_all(b"#[allow(dead_code)]\npub fn print_build_info() {\n")?;
+            for var in &self.variables {
+                writeln!(w, "{indent}println!(\"{0}: {{:?}}\", {0});", var.name)?;
+            }
+            w.write_all(b"}\n")?;
+        }
+
+        if self.options.emit_macro {
+            if doc {
+                w.write_all(
+                    b"/// Expands to the value of a variable in this file, given its name in lowercase, e.g. `build_info!(pkg_version)`.\n",
+                )?;
+            }
+            w.write_all(b"#[allow(unused_macros)]\nmacro_rules! build_info {\n")?;
+            for var in &self.variables {
+                writeln!(
+                    w,
+                    "{indent}({}) => {{{{ const VALUE: {} = {}; VALUE }}}};",
+                    var.name.to_lowercase(),
+                    var.datatype,
+                    var.value
+                )?;
+            }
+            w.write_all(b"}\n")?;
+        }
+
+        w.write_all(
+            b"//\n// EVERYTHING ABOVE THIS POINT WAS AUTO-GENERATED DURING COMPILATION. DO NOT MODIFY.\n//\n",
+        )
+    }
+
+    /// The collected variables by name, with their plain values.
+    pub(crate) fn into_map(self) -> collections::BTreeMap<String, Option<String>> {
+        self.variables
+            .into_iter()
+            .map(|var| {
+                let value = util::plain_value(&var.value);
+                (var.name, value)
+            })
+            .collect()
+    }
+
+    /// Writes the collected variables as `KEY=VALUE`-lines, as understood by shells and Docker.
+    fn write_env(&self, w: &mut impl io::Write) -> io::Result<()> {
+        for var in &self.variables {
+            if let Some(value) = util::plain_value(&var.value) {
+                writeln!(w, "{}={}", var.name, env_quote(&value))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Quotes `value` for use in a `.env`-file, if it contains whitespace or special characters.
+fn env_quote(value: &str) -> std::borrow::Cow<'_, str> {
+    if value.is_empty()
+        || value
+            .chars()
+            .any(|c| c.is_whitespace() || "\"'\\#$`".contains(c))
+    {
+        let mut quoted = String::with_capacity(value.len() + 2);
+        quoted.push('"');
+        for c in value.chars() {
+            match c {
+                '\n' => quoted.push_str("\\n"),
+                '"' | '\\' | '$' | '`' => {
+                    quoted.push('\\');
+                    quoted.push(c);
+                }
+                c => quoted.push(c),
+            }
+        }
+        quoted.push('"');
+        quoted.into()
+    } else {
+        value.into()
+    }
+}
+
+/// Writes rust-code describing the crate at `manifest_location` to a file named `dst`.
+///
+/// This is a shorthand for calling `write_built_file_with_options()` with `Options::default()`.
 ///
 /// # Errors
-/// The function returns an error if the file at `dst` already exists or can't
-/// be written to. This should not be a concern if the filename points to
-/// `OUR_DIR`.
+/// The function returns an error if the information can't be collected, e.g. due to
+/// `Options::set_fail_if_dirty()`, or if the file at `dst` can't be written to. An existing
+/// file at `dst` is replaced atomically, so it never appears partially written, and is left
+/// untouched if its content is identical. This should not be a concern if the filename
+/// points to `OUT_DIR`.
 pub fn write_built_file_with_opts(
     #[cfg(any(feature = "cargo-lock", feature = "git2", feature = "gix"))]
     manifest_location: Option<&path::Path>,
     dst: &path::Path,
 ) -> io::Result<()> {
-    let mut built_file = fs::File::create(dst)?;
-    built_file.write_all(
-        r#"//
-// EVERYTHING BELOW THIS POINT WAS AUTO-GENERATED DURING COMPILATION. DO NOT MODIFY.
-//
-"#
-        .as_ref(),
+    write_built_file_with_options(
+        &Options::default(),
+        #[cfg(any(feature = "cargo-lock", feature = "git2", feature = "gix"))]
+        manifest_location,
+        dst,
+    )
+}
+
+/// Writes rust-code describing the crate at `manifest_location` to a file named `dst`,
+/// collecting only the information selected by `options`.
+///
+/// # Errors
+/// Same as `write_built_file_with_opts()`.
+pub fn write_built_file_with_options(
+    options: &Options,
+    #[cfg(any(feature = "cargo-lock", feature = "git2", feature = "gix"))]
+    manifest_location: Option<&path::Path>,
+    dst: &path::Path,
+) -> io::Result<()> {
+    let mut built_file = BuiltWriter::new(options);
+    write_variables(
+        options,
+        #[cfg(any(feature = "cargo-lock", feature = "git2", feature = "gix"))]
+        manifest_location,
+        &mut built_file,
     )?;
+    util::write_file_atomically(dst, |w| built_file.write_rust(w))
+}
 
+/// Writes the same information as `write_built_file_with_options()` as `KEY=VALUE`-lines
+/// to a file named `dst`, for consumption by shell-scripts or Docker.
+///
+/// Values containing whitespace or special characters are double-quoted; optional values
+/// which are `None` are omitted entirely. Arrays are written as a single, comma-separated
+/// value, like their `_STR`-counterparts.
+///
+/// ```text
+/// PKG_VERSION=0.1.0
+/// GIT_COMMIT_HASH=ba14bb2a3ab8d7ff2ba8b5a9d6e8e2b2c9b8d0c8
+/// RUSTC_VERSION="rustc 1.79.0 (129f3b996 2024-06-10)"
+/// ```
+///
+/// # Errors
+/// Same as `write_built_file_with_opts()`.
+pub fn write_built_env(
+    options: &Options,
+    #[cfg(any(feature = "cargo-lock", feature = "git2", feature = "gix"))]
+    manifest_location: Option<&path::Path>,
+    dst: &path::Path,
+) -> io::Result<()> {
+    let mut built_file = BuiltWriter::new(options);
+    write_variables(
+        options,
+        #[cfg(any(feature = "cargo-lock", feature = "git2", feature = "gix"))]
+        manifest_location,
+        &mut built_file,
+    )?;
+    util::write_file_atomically(dst, |w| built_file.write_env(w))
+}
+
+/// Collects the information selected by `options` and writes it to `built_file`.
+pub(crate) fn write_variables(
+    options: &Options,
+    #[cfg(any(feature = "cargo-lock", feature = "git2", feature = "gix"))]
+    manifest_location: Option<&path::Path>,
+    built_file: &mut BuiltWriter<'_>,
+) -> io::Result<()> {
+    let start = std::time::Instant::now();
     let envmap = environment::EnvironmentMap::new();
-    envmap.write_ci(&built_file)?;
-    envmap.write_env(&built_file)?;
-    envmap.write_features(&built_file)?;
-    envmap.write_compiler_version(&built_file)?;
-    envmap.write_cfg(&built_file)?;
+    let in_ide = envmap
+        .get_override_var("BUILT_IN_IDE")
+        .unwrap_or_else(|| envmap.detect_ide());
+    #[cfg(any(feature = "cargo-metadata", feature = "git2", feature = "gix"))]
+    let skip_collection = options.skip_in_ide && in_ide;
+    if options.ci {
+        envmap.write_ci(built_file)?;
+    }
+    if options.env {
+        envmap.write_env(built_file)?;
+    }
+    if options.features {
+        envmap.write_features(built_file)?;
+    }
+    if options.compiler {
+        envmap.write_compiler_version(built_file)?;
+    }
+    if options.cfg {
+        envmap.write_cfg(built_file)?;
+    }
+    if options.build_os {
+        envmap.write_build_os(built_file)?;
+    }
+    if options.rustc_sysroot {
+        envmap.write_rustc_sysroot(built_file)?;
+    }
+
+    #[cfg(feature = "cargo-metadata")]
+    if options.metadata {
+        metadata::write_metadata(
+            &envmap,
+            &options.cargo(&envmap),
+            skip_collection,
+            built_file,
+        )?;
+    }
 
     #[cfg(any(feature = "git2", feature = "gix"))]
-    {
+    if options.git {
+        if let Some(git_root) = options.git_root.as_deref().or(manifest_location) {
+            git_shared::write_git_version(
+                git_root,
+                options.git_exact_root,
+                options,
+                &envmap,
+                skip_collection,
+                built_file,
+            )?;
+        }
+        git_shared::write_named_git_sources(options, &envmap, skip_collection, built_file)?;
+    }
+
+    #[cfg(feature = "cargo-lock")]
+    if options.require_locked {
         if let Some(manifest_location) = manifest_location {
-            git_shared::write_git_version(manifest_location, &envmap, &built_file)?;
+            dependencies::check_locked(manifest_location, &options.cargo(&envmap), built_file)?;
         }
     }
 
     #[cfg(feature = "cargo-lock")]
-    if let Some(manifest_location) = manifest_location {
-        dependencies::write_dependencies(manifest_location, &built_file)?;
+    if options.dependencies {
+        if let Some(manifest_location) = manifest_location {
+            #[cfg(feature = "cargo-metadata")]
+            let resolved = options.dependencies_resolved;
+            #[cfg(not(feature = "cargo-metadata"))]
+            let resolved = false;
+            if resolved {
+                #[cfg(feature = "cargo-metadata")]
+                dependencies::write_resolved_dependencies(
+                    manifest_location,
+                    &envmap,
+                    &options.cargo(&envmap),
+                    options.dependency_versions,
+                    options.max_dependencies,
+                    built_file,
+                )?;
+            } else {
+                dependencies::write_dependencies(
+                    manifest_location,
+                    options.dependency_versions,
+                    options.max_dependencies,
+                    built_file,
+                )?;
+            }
+        }
     }
 
     #[cfg(feature = "chrono")]
-    krono::write_time(&built_file, &envmap)?;
-
-    let mut used_override_vars = envmap.used_override_vars().collect::<Vec<_>>();
-    used_override_vars.sort_unstable();
-    write_variable!(
-        built_file,
-        "OVERRIDE_VARIABLES_USED",
-        format_args!("[&str; {}]", used_override_vars.len()),
-        util::ArrayDisplay(&used_override_vars, |t, f| write!(
-            f,
-            "\"{}\"",
-            t.escape_default()
-        )),
-        "The override-variables that were used during compilation."
-    );
-
-    built_file.write_all(
-        r#"//
-// EVERYTHING ABOVE THIS POINT WAS AUTO-GENERATED DURING COMPILATION. DO NOT MODIFY.
-//
-"#
-        .as_ref(),
-    )?;
+    if options.time {
+        krono::write_time(
+            built_file,
+            &envmap,
+            options.time_components,
+            options.time_local,
+        )?;
+    }
+
+    if let Some(channel) = &options.build_channel {
+        let channel = envmap
+            .get_override_var("BUILD_CHANNEL")
+            .unwrap_or_else(|| match channel {
+                options::BuildChannel::Verbatim(channel) => channel.clone(),
+                options::BuildChannel::Env(var) => {
+                    built_file.directive(format_args!("rerun-if-env-changed={var}"));
+                    envmap.get(var).unwrap_or_default().to_owned()
+                }
+            });
+        write_str_variable!(
+            built_file,
+            "BUILD_CHANNEL",
+            channel,
+            "The release-channel or -stage of the build, e.g. `nightly` or `canary`."
+        );
+    }
+
+    // Added explicitly by the user, therefore not subject to the selection of `Options::minimal()`
+    for Variable {
+        name,
+        datatype,
+        value,
+        doc,
+    } in &options.custom_variables
+    {
+        built_file.write_variable(
+            name,
+            format_args!("{datatype}"),
+            format_args!("{value}"),
+            format_args!("{doc}"),
+        )?;
+    }
+
+    if options.rerun_if_changed {
+        let mut rerun_paths = built_file
+            .rerun_paths
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect::<Vec<_>>();
+        rerun_paths.sort_unstable();
+        rerun_paths.dedup();
+        for path in &rerun_paths {
+            built_file.directive(format_args!("rerun-if-changed={path}"));
+        }
+        write_variable!(
+            built_file,
+            "BUILT_RERUN_PATHS",
+            format_args!("[&str; {}]", rerun_paths.len()),
+            util::ArrayDisplay(&rerun_paths, |t, f| write!(f, "\"{}\"", t.escape_default())),
+            "The paths `built` registered via `cargo::rerun-if-changed`."
+        );
+    }
+
+    if options.built_meta {
+        write_variable!(
+            built_file,
+            "BUILT_IN_IDE",
+            "bool",
+            in_ide,
+            "`true` if the build-script was run by an IDE, i.e. `rust-analyzer`, instead of a build."
+        );
+
+        let fingerprint = envmap
+            .get_override_var("BUILD_FINGERPRINT")
+            .unwrap_or_else(|| built_file.fingerprint());
+        write_str_variable!(
+            built_file,
+            "BUILD_FINGERPRINT",
+            fingerprint,
+            "A hash over `GIT_COMMIT_HASH`, `DEPENDENCIES`, `RUSTC_VERSION`, `TARGET` and \
+            `FEATURES`, as far as they were collected; it does not depend on the time of the build."
+        );
+
+        let mut used_override_vars = envmap.used_override_vars().collect::<Vec<_>>();
+        used_override_vars.sort_unstable();
+        write_variable!(
+            built_file,
+            "OVERRIDE_VARIABLES_USED",
+            format_args!("[&str; {}]", used_override_vars.len()),
+            util::ArrayDisplay(&used_override_vars, |t, f| write!(
+                f,
+                "\"{}\"",
+                t.escape_default()
+            )),
+            "The override-variables that were used during compilation."
+        );
+    }
+
+    if options.collection_duration && env::var(SOURCE_DATE_EPOCH).is_err() {
+        let duration_ms = envmap
+            .get_override_var("BUILT_COLLECTION_DURATION_MS")
+            .unwrap_or_else(|| u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX));
+        write_variable!(
+            built_file,
+            "BUILT_COLLECTION_DURATION_MS",
+            "u64",
+            duration_ms,
+            "The time in milliseconds `built` took to collect this information; this does not \
+            include the rest of the build."
+        );
+    }
 
     let unused_override_vars = envmap.unused_override_vars().collect::<Vec<_>>().join(", ");
     if !unused_override_vars.is_empty() {
-        println!("cargo::warning=At least one environment variable looks like an override-variable but was ignored by built: `{unused_override_vars}`. Typo?");
+        built_file.directive(format_args!("warning=At least one environment variable looks like an override-variable but was ignored by built: `{unused_override_vars}`. Typo?"));
     }
 
     Ok(())
 }
 
+/// Reads the variables from a file previously written by `built`, e.g. to verify the
+/// generated output in tests without `include!()`ing it.
+///
+/// Every variable is mapped to its value as the Rust-literal that was written, e.g.
+/// `"0.1.0"` (including the quotes) or `Some(true)`; strings written once via
+/// `Options::set_intern_strings()` are put back in place. Only the format written by
+/// `built` is understood, not arbitrary Rust-code.
+///
+/// ```rust,no_run
+/// let vars = built::parse_built_file("target/debug/build/mycrate-1234/out/built.rs".as_ref())
+///     .unwrap();
+/// assert_eq!(vars["PKG_VERSION"], r#""0.1.0""#);
+/// ```
+///
+/// # Errors
+/// If the file can't be read.
+pub fn parse_built_file(
+    built_file: &path::Path,
+) -> io::Result<collections::BTreeMap<String, String>> {
+    let content = std::fs::read_to_string(built_file)?;
+    let mut interned = Vec::new();
+    let mut variables = collections::BTreeMap::new();
+    for line in content.lines() {
+        let (declaration, is_interned) = match line.strip_prefix("pub static ") {
+            Some(declaration) => (declaration, false),
+            None => match line.strip_prefix("const ") {
+                Some(declaration) => (declaration, true),
+                None => continue,
+            },
+        };
+        let Some((name, rest)) = declaration.split_once(": ") else {
+            continue;
+        };
+        let Some((_, value)) = rest.split_once(" = ") else {
+            continue;
+        };
+        let value = value.strip_suffix(';').unwrap_or(value).to_owned();
+        if is_interned {
+            interned.push((name.to_owned(), value));
+        } else {
+            variables.insert(name.to_owned(), value);
+        }
+    }
+
+    // Longest names first, so `__BUILT_STR_1` does not replace a part of `__BUILT_STR_10`.
+    interned.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
+    for value in variables.values_mut() {
+        for (name, literal) in &interned {
+            if value.contains(name.as_str()) {
+                *value = value.replace(name.as_str(), literal);
+            }
+        }
+    }
+    Ok(variables)
+}
+
 /// A shorthand for calling `write_built_file_with_opts()` with `CARGO_MANIFEST_DIR` and
 /// `[OUT_DIR]/built.rs`.
 ///
@@ -555,14 +1719,19 @@ pub fn write_built_file_with_opts(
 /// Same as `write_built_file_with_opts()`.
 ///
 /// # Panics
-/// If `CARGO_MANIFEST_DIR` or `OUT_DIR` are not set.
+/// If `CARGO_MANIFEST_DIR` or `OUT_DIR` are not set, i.e. if not called from a build-script.
 pub fn write_built_file() -> io::Result<()> {
-    let dst = path::Path::new(&env::var("OUT_DIR").expect("OUT_DIR not set")).join("built.rs");
+    const NOT_A_BUILD_SCRIPT: &str =
+        "`built::write_built_file()` must be called from a build-script";
+    let dst = path::Path::new(
+        &env::var("OUT_DIR").unwrap_or_else(|_| panic!("OUT_DIR not set; {NOT_A_BUILD_SCRIPT}")),
+    )
+    .join("built.rs");
     write_built_file_with_opts(
         #[cfg(any(feature = "cargo-lock", feature = "git2", feature = "gix"))]
         Some(
             env::var("CARGO_MANIFEST_DIR")
-                .expect("CARGO_MANIFEST_DIR")
+                .unwrap_or_else(|_| panic!("CARGO_MANIFEST_DIR not set; {NOT_A_BUILD_SCRIPT}"))
                 .as_ref(),
         ),
         &dst,
@@ -0,0 +1,186 @@
+use crate::util::ArrayDisplay;
+use crate::{environment, fmt_option_str, write_str_variable, write_variable, BuiltWriter};
+use std::{io, path};
+
+/// Retrieves the package described by the manifest in `manifest_dir` from `cargo metadata`,
+/// running the `cargo`-binary `cargo`.
+fn find_package(
+    manifest_dir: &path::Path,
+    cargo: &path::Path,
+) -> io::Result<cargo_metadata::Package> {
+    let manifest_path = manifest_dir.join("Cargo.toml");
+    let metadata = cargo_metadata::MetadataCommand::new()
+        .cargo_path(cargo)
+        .manifest_path(&manifest_path)
+        .no_deps()
+        .exec()
+        .map_err(io::Error::other)?;
+    metadata
+        .packages
+        .into_iter()
+        .find(|p| p.manifest_path.as_std_path() == manifest_path)
+        .ok_or_else(|| io::Error::other("Package not found in `cargo metadata`"))
+}
+
+/// Writes `values` as an array of strings `name`.
+fn write_str_array(
+    w: &mut BuiltWriter<'_>,
+    name: &str,
+    values: &[String],
+    doc: &str,
+) -> io::Result<()> {
+    write_variable!(
+        w,
+        name,
+        format_args!("[&str; {}]", values.len()),
+        ArrayDisplay(values, |t, f| write!(f, "\"{}\"", t.escape_default())),
+        doc
+    );
+    Ok(())
+}
+
+pub(crate) fn write_metadata(
+    envmap: &environment::EnvironmentMap,
+    cargo: &path::Path,
+    skip_collection: bool,
+    w: &mut BuiltWriter<'_>,
+) -> io::Result<()> {
+    let edition: Option<String> = envmap.get_override_var("PKG_EDITION");
+    let metadata: Option<Option<String>> = envmap.get_override_var("PKG_METADATA");
+    let features_available: Option<Vec<String>> = envmap.get_override_var("FEATURES_AVAILABLE");
+    let categories: Option<Vec<String>> = envmap.get_override_var("PKG_CATEGORIES");
+    let keywords: Option<Vec<String>> = envmap.get_override_var("PKG_KEYWORDS");
+    let links: Option<Option<String>> = envmap.get_override_var("PKG_LINKS");
+    let default_run: Option<Option<String>> = envmap.get_override_var("PKG_DEFAULT_RUN");
+    let readme: Option<Option<String>> = envmap.get_override_var("PKG_README");
+    let documentation: Option<Option<String>> = envmap.get_override_var("PKG_DOCUMENTATION");
+
+    // Running `cargo metadata` is expensive, avoid it if everything is overridden.
+    let package = if skip_collection
+        || (edition.is_some()
+            && metadata.is_some()
+            && features_available.is_some()
+            && categories.is_some()
+            && keywords.is_some()
+            && links.is_some()
+            && default_run.is_some()
+            && readme.is_some()
+            && documentation.is_some())
+    {
+        None
+    } else {
+        let manifest_dir = envmap
+            .get("CARGO_MANIFEST_DIR")
+            .expect("Missing expected environment variable CARGO_MANIFEST_DIR");
+        let package = find_package(manifest_dir.as_ref(), cargo)?;
+        w.rerun_if_changed(package.manifest_path.clone().into());
+        Some(package)
+    };
+
+    let edition = edition
+        .or_else(|| package.as_ref().map(|p| p.edition.to_string()))
+        .unwrap_or_default();
+    write_str_variable!(
+        w,
+        "PKG_EDITION",
+        edition,
+        "The Rust edition of the package, e.g. `2021`."
+    );
+
+    let metadata = metadata.unwrap_or_else(|| {
+        package
+            .as_ref()
+            .map(|p| &p.metadata)
+            .filter(|m| !m.is_null() && m.as_object().is_none_or(|m| !m.is_empty()))
+            .map(ToString::to_string)
+    });
+    write_variable!(
+        w,
+        "PKG_METADATA",
+        "Option<&str>",
+        fmt_option_str(metadata.map(|m| m.escape_default().to_string())),
+        "The `[package.metadata]`-table of the package as JSON; `None` if it is empty."
+    );
+
+    let mut features_available = features_available.unwrap_or_else(|| {
+        package
+            .as_ref()
+            .map(|p| p.features.keys().cloned().collect())
+            .unwrap_or_default()
+    });
+    features_available.sort_unstable();
+    write_str_array(
+        w,
+        "FEATURES_AVAILABLE",
+        &features_available,
+        "The features declared by the package, sorted; `default` is only included if declared.",
+    )?;
+
+    let categories = categories
+        .or_else(|| package.as_ref().map(|p| p.categories.clone()))
+        .unwrap_or_default();
+    write_str_array(
+        w,
+        "PKG_CATEGORIES",
+        &categories,
+        "The `categories` of the package, in the order of the manifest.",
+    )?;
+
+    let keywords = keywords
+        .or_else(|| package.as_ref().map(|p| p.keywords.clone()))
+        .unwrap_or_default();
+    write_str_array(
+        w,
+        "PKG_KEYWORDS",
+        &keywords,
+        "The `keywords` of the package, in the order of the manifest.",
+    )?;
+
+    let links = links.unwrap_or_else(|| package.as_ref().and_then(|p| p.links.clone()));
+    write_variable!(
+        w,
+        "PKG_LINKS",
+        "Option<&str>",
+        fmt_option_str(links.map(|l| l.escape_default().to_string())),
+        "The native library the package links to, as declared by its `links`-key; `None` \
+        if there is none."
+    );
+
+    let default_run =
+        default_run.unwrap_or_else(|| package.as_ref().and_then(|p| p.default_run.clone()));
+    write_variable!(
+        w,
+        "PKG_DEFAULT_RUN",
+        "Option<&str>",
+        fmt_option_str(default_run.map(|d| d.escape_default().to_string())),
+        "The binary `cargo run` runs by default, as declared by the `default-run`-key; `None` \
+        if there is none."
+    );
+
+    let readme = readme.unwrap_or_else(|| {
+        package
+            .as_ref()
+            .and_then(|p| p.readme.as_ref().map(ToString::to_string))
+    });
+    write_variable!(
+        w,
+        "PKG_README",
+        "Option<&str>",
+        fmt_option_str(readme.map(|r| r.escape_default().to_string())),
+        "The path to the readme of the package, as declared by its `readme`-key or found by \
+        Cargo; `None` if there is none."
+    );
+
+    let documentation =
+        documentation.unwrap_or_else(|| package.as_ref().and_then(|p| p.documentation.clone()));
+    write_variable!(
+        w,
+        "PKG_DOCUMENTATION",
+        "Option<&str>",
+        fmt_option_str(documentation.map(|d| d.escape_default().to_string())),
+        "The URL of the documentation of the package, as declared by its \
+        `documentation`-key; `None` if there is none."
+    );
+
+    Ok(())
+}
@@ -0,0 +1,1265 @@
+/// Controls which information `built` collects and writes.
+///
+/// The default collects everything the enabled feature-flags make available;
+/// individual groups of variables can be turned off. Disabling a group not only
+/// omits the variables from the generated file, but also skips the work required
+/// to collect them (e.g. running `rustc -V` or parsing `Cargo.lock`).
+///
+/// ```rust,no_run
+/// let dst = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("built.rs");
+/// let mut options = built::Options::default();
+/// options.set_cfg(false).set_ci(false);
+/// built::write_built_file_with_options(
+///     &options,
+///     # #[cfg(any(feature = "cargo-lock", feature = "git2", feature = "gix"))]
+///     Some(std::env::var("CARGO_MANIFEST_DIR").unwrap().as_ref()),
+///     &dst,
+/// )
+/// .expect("Failed to acquire build-time information");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub(crate) ci: bool,
+    pub(crate) env: bool,
+    pub(crate) features: bool,
+    pub(crate) compiler: bool,
+    pub(crate) cfg: bool,
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub(crate) git: bool,
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub(crate) git_root: Option<std::path::PathBuf>,
+    /// If `git_root` is the exact root of the repository, whether to fall back to discovery.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub(crate) git_exact_root: Option<bool>,
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub(crate) named_git_sources: Vec<(String, std::path::PathBuf)>,
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub(crate) git_contributor_count: bool,
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub(crate) git_commits_count: bool,
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub(crate) git_commit_depth: bool,
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub(crate) git_commit_files_changed: bool,
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub(crate) git_commit_hash_bytes: bool,
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub(crate) git_reachable_tags: Option<usize>,
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub(crate) git_dirty_include_ignored: bool,
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub(crate) git_dirty_statuses: DirtyStatuses,
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub(crate) git_dirty_paths: Vec<std::path::PathBuf>,
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub(crate) git_dirty_lines: bool,
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub(crate) git_dirty_files: Option<usize>,
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub(crate) source_tree_hash: bool,
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub(crate) fail_if_dirty: bool,
+    #[cfg(all(feature = "verify-signature", any(feature = "git2", feature = "gix")))]
+    pub(crate) git_verify_commit_signature: bool,
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub(crate) git_commit_notes: bool,
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub(crate) git_describe_committish: Option<String>,
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub(crate) git_base_branch: Option<String>,
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub(crate) git_commit_on_remote: bool,
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub(crate) git_describe_all: bool,
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub(crate) git_committish: Option<String>,
+    #[cfg(all(feature = "chrono", any(feature = "git2", feature = "gix")))]
+    pub(crate) git_time_format: TimeFormat,
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub(crate) git_contributor_count_limit: Option<usize>,
+    #[cfg(feature = "cargo-metadata")]
+    pub(crate) metadata: bool,
+    #[cfg(feature = "cargo-lock")]
+    pub(crate) dependencies: bool,
+    #[cfg(feature = "cargo-lock")]
+    pub(crate) dependency_versions: bool,
+    #[cfg(feature = "cargo-lock")]
+    pub(crate) max_dependencies: Option<usize>,
+    #[cfg(feature = "cargo-lock")]
+    pub(crate) require_locked: bool,
+    #[cfg(all(feature = "cargo-lock", feature = "cargo-metadata"))]
+    pub(crate) dependencies_resolved: bool,
+    #[cfg(any(feature = "cargo-lock", feature = "cargo-metadata"))]
+    pub(crate) cargo_path: Option<std::path::PathBuf>,
+    #[cfg(feature = "chrono")]
+    pub(crate) time: bool,
+    #[cfg(feature = "chrono")]
+    pub(crate) time_components: bool,
+    #[cfg(feature = "chrono")]
+    pub(crate) time_local: bool,
+    pub(crate) rerun_if_changed: bool,
+    pub(crate) built_meta: bool,
+    pub(crate) collection_duration: bool,
+    pub(crate) intern_strings: bool,
+    pub(crate) unwrap_known_values: bool,
+    pub(crate) cow_strings: bool,
+    pub(crate) emit_print_fn: bool,
+    pub(crate) emit_map: bool,
+    pub(crate) emit_macro: bool,
+    pub(crate) compact: bool,
+    pub(crate) skip_in_ide: bool,
+    pub(crate) build_channel: Option<BuildChannel>,
+    pub(crate) build_os: bool,
+    pub(crate) rustc_sysroot: bool,
+    pub(crate) hash_algorithm: HashAlgorithm,
+    pub(crate) custom_variables: Vec<crate::Variable>,
+    variables: Option<&'static [&'static str]>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            ci: true,
+            env: true,
+            features: true,
+            compiler: true,
+            cfg: true,
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            git: true,
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            git_root: None,
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            git_exact_root: None,
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            named_git_sources: Vec::new(),
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            git_contributor_count: false,
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            git_commits_count: false,
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            git_commit_depth: false,
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            git_commit_files_changed: false,
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            git_commit_hash_bytes: false,
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            git_reachable_tags: None,
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            git_dirty_include_ignored: false,
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            git_dirty_statuses: DirtyStatuses::default(),
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            git_dirty_paths: Vec::new(),
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            git_dirty_lines: false,
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            git_dirty_files: None,
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            source_tree_hash: false,
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            fail_if_dirty: false,
+            #[cfg(all(feature = "verify-signature", any(feature = "git2", feature = "gix")))]
+            git_verify_commit_signature: false,
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            git_commit_notes: false,
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            git_describe_committish: None,
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            git_base_branch: None,
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            git_commit_on_remote: false,
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            git_describe_all: false,
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            git_committish: None,
+            #[cfg(all(feature = "chrono", any(feature = "git2", feature = "gix")))]
+            git_time_format: TimeFormat::Original,
+            #[cfg(any(feature = "git2", feature = "gix"))]
+            git_contributor_count_limit: None,
+            #[cfg(feature = "cargo-metadata")]
+            metadata: true,
+            #[cfg(feature = "cargo-lock")]
+            dependencies: true,
+            #[cfg(feature = "cargo-lock")]
+            dependency_versions: true,
+            #[cfg(feature = "cargo-lock")]
+            max_dependencies: None,
+            #[cfg(feature = "cargo-lock")]
+            require_locked: false,
+            #[cfg(all(feature = "cargo-lock", feature = "cargo-metadata"))]
+            dependencies_resolved: false,
+            #[cfg(any(feature = "cargo-lock", feature = "cargo-metadata"))]
+            cargo_path: None,
+            #[cfg(feature = "chrono")]
+            time: true,
+            #[cfg(feature = "chrono")]
+            time_components: false,
+            #[cfg(feature = "chrono")]
+            time_local: false,
+            rerun_if_changed: false,
+            built_meta: true,
+            collection_duration: false,
+            intern_strings: false,
+            unwrap_known_values: false,
+            cow_strings: false,
+            emit_print_fn: false,
+            emit_map: false,
+            emit_macro: false,
+            compact: false,
+            skip_in_ide: false,
+            build_channel: None,
+            build_os: false,
+            rustc_sysroot: false,
+            hash_algorithm: HashAlgorithm::default(),
+            custom_variables: Vec::new(),
+            variables: None,
+        }
+    }
+}
+
+impl Options {
+    /// A curated, size-conscious subset of the available information.
+    ///
+    /// Only the following variables are written, given that the respective
+    /// feature-flags are enabled:
+    ///
+    /// * `PKG_VERSION`
+    /// * `GIT_COMMIT_HASH_SHORT` (`git2` or `gix`)
+    /// * `BUILT_TIME_UTC` (`chrono`)
+    ///
+    /// Notably, `OVERRIDE_VARIABLES_USED` is not written either. The
+    /// Continuous Integration platform, features, compiler, cfg, `cargo metadata` and
+    /// dependencies are not collected at all.
+    #[must_use]
+    pub fn minimal() -> Self {
+        Self {
+            ci: false,
+            features: false,
+            compiler: false,
+            cfg: false,
+            #[cfg(feature = "cargo-metadata")]
+            metadata: false,
+            #[cfg(feature = "cargo-lock")]
+            dependencies: false,
+            built_meta: false,
+            variables: Some(&["PKG_VERSION", "GIT_COMMIT_HASH_SHORT", "BUILT_TIME_UTC"]),
+            ..Self::default()
+        }
+    }
+
+    /// Only git-information, for crates which use `built` solely to stamp the commit.
+    ///
+    /// Only the `GIT_*`-variables are written, including those of named git-sources; the
+    /// same ones as for `Options::default()`. The Continuous Integration platform, package
+    /// metadata, features, compiler, cfg, `cargo metadata`, dependencies and the build-time
+    /// are not collected at all, and neither are `BUILT_IN_IDE`, `BUILD_FINGERPRINT` and
+    /// `OVERRIDE_VARIABLES_USED`. Variables added via
+    /// `Options::add_variable()` are still written.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    #[must_use]
+    pub fn git_only() -> Self {
+        Self {
+            ci: false,
+            env: false,
+            features: false,
+            compiler: false,
+            cfg: false,
+            #[cfg(feature = "cargo-metadata")]
+            metadata: false,
+            #[cfg(feature = "cargo-lock")]
+            dependencies: false,
+            #[cfg(feature = "chrono")]
+            time: false,
+            built_meta: false,
+            ..Self::default()
+        }
+    }
+
+    /// Detect the Continuous Integration platform, e.g. `CI_PLATFORM`.
+    pub fn set_ci(&mut self, enabled: bool) -> &mut Self {
+        self.ci = enabled;
+        self
+    }
+
+    /// Package metadata and build environment, e.g. `PKG_VERSION`, `TARGET` and `PROFILE`.
+    pub fn set_env(&mut self, enabled: bool) -> &mut Self {
+        self.env = enabled;
+        self
+    }
+
+    /// The features that were enabled during compilation, e.g. `FEATURES`.
+    pub fn set_features(&mut self, enabled: bool) -> &mut Self {
+        self.features = enabled;
+        self
+    }
+
+    /// The compiler and documentation-generator versions, e.g. `RUSTC_VERSION`.
+    pub fn set_compiler(&mut self, enabled: bool) -> &mut Self {
+        self.compiler = enabled;
+        self
+    }
+
+    /// The `CARGO_CFG_*`-derived values, e.g. `CFG_OS`.
+    pub fn set_cfg(&mut self, enabled: bool) -> &mut Self {
+        self.cfg = enabled;
+        self
+    }
+
+    /// Git-information, e.g. `GIT_VERSION` and `GIT_COMMIT_HASH`.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub fn set_git(&mut self, enabled: bool) -> &mut Self {
+        self.git = enabled;
+        self
+    }
+
+    /// Discover the git-repository at or above `root`, instead of the manifest's location.
+    ///
+    /// This is useful if the manifest does not reside within the source's repository,
+    /// e.g. for generated manifests or out-of-tree builds.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub fn set_git_root(&mut self, root: impl Into<std::path::PathBuf>) -> &mut Self {
+        self.git_root = Some(root.into());
+        self.git_exact_root = None;
+        self
+    }
+
+    /// Open the git-repository at exactly `root`, i.e. the directory containing `.git`,
+    /// instead of discovering it at or above the manifest's location.
+    ///
+    /// The repository is then looked for in `root` only, without walking its parents,
+    /// which saves some work if the build-script already knows the repository's root,
+    /// e.g. in large monorepos. If there is no repository at `root`, it is discovered at or
+    /// above `root` as with `Options::set_git_root()` if `discover_on_failure` is `true`;
+    /// otherwise no git-information is collected, as if there was no repository at all.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub fn set_git_exact_root(
+        &mut self,
+        root: impl Into<std::path::PathBuf>,
+        discover_on_failure: bool,
+    ) -> &mut Self {
+        self.git_root = Some(root.into());
+        self.git_exact_root = Some(discover_on_failure);
+        self
+    }
+
+    /// Additionally write the git-information of the repository at or above `root`, with
+    /// every variable prefixed by the uppercase `name`, e.g. `APP_GIT_VERSION` for `app`.
+    ///
+    /// This is useful if the build includes sources from multiple repositories. May be
+    /// called multiple times.
+    ///
+    /// # Panics
+    /// If `name` is not a valid identifier, or was already added.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub fn add_named_git_source(
+        &mut self,
+        name: &str,
+        root: impl Into<std::path::PathBuf>,
+    ) -> &mut Self {
+        let name = name.to_uppercase().replace('-', "_");
+        assert!(
+            name.starts_with(|c: char| c.is_ascii_alphabetic())
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'),
+            "The name of a git-source must be a valid identifier, got `{name}`"
+        );
+        assert!(
+            self.named_git_sources.iter().all(|(n, _)| *n != name),
+            "The git-source `{name}` was already added"
+        );
+        self.named_git_sources.push((name, root.into()));
+        self
+    }
+
+    /// Count the unique author-emails in the history reachable from HEAD as
+    /// `GIT_CONTRIBUTOR_COUNT`.
+    ///
+    /// This requires walking the entire history and is therefore disabled by default.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub fn set_git_contributor_count(&mut self, enabled: bool) -> &mut Self {
+        self.git_contributor_count = enabled;
+        self
+    }
+
+    /// Count the commits in the history reachable from HEAD as `GIT_COMMITS_COUNT`.
+    ///
+    /// This requires walking the entire history and is therefore disabled by default.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub fn set_git_commits_count(&mut self, enabled: bool) -> &mut Self {
+        self.git_commits_count = enabled;
+        self
+    }
+
+    /// Count the commits on the first-parent chain from HEAD back to the root commit as
+    /// `GIT_COMMIT_DEPTH`, e.g. for use as a build-number that is stable under merges.
+    ///
+    /// This requires walking the history and is therefore disabled by default.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub fn set_git_commit_depth(&mut self, enabled: bool) -> &mut Self {
+        self.git_commit_depth = enabled;
+        self
+    }
+
+    /// Count the files changed by HEAD's commit compared to its first parent as
+    /// `GIT_COMMIT_FILES_CHANGED`, e.g. to tell small fixes from large changes.
+    ///
+    /// Unlike `GIT_DIRTY`, this characterizes the commit itself, not the working tree. It
+    /// requires diffing two trees and is therefore disabled by default.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub fn set_git_commit_files_changed(&mut self, enabled: bool) -> &mut Self {
+        self.git_commit_files_changed = enabled;
+        self
+    }
+
+    /// Additionally write HEAD's commit hash as raw bytes, as `GIT_COMMIT_HASH_BYTES`, so
+    /// it can be compared without decoding `GIT_COMMIT_HASH` at runtime, e.g. on embedded
+    /// targets.
+    ///
+    /// The array has 20 elements, or 32 if the repository uses SHA-256. The default is `false`.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub fn set_git_commit_hash_bytes(&mut self, enabled: bool) -> &mut Self {
+        self.git_commit_hash_bytes = enabled;
+        self
+    }
+
+    /// Write the names of up to `max_tags` tags pointing at HEAD's commit or any of its
+    /// ancestors as `GIT_REACHABLE_TAGS`, e.g. for changelog-tooling.
+    ///
+    /// The tags are sorted by the date of the commit they point at, the newest first, and by
+    /// name if those are equal; so the most recent `max_tags` releases are kept. This
+    /// requires walking the entire history and is therefore disabled by default. The
+    /// array is empty for shallow clones.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub fn set_git_reachable_tags(&mut self, max_tags: usize) -> &mut Self {
+        self.git_reachable_tags = Some(max_tags);
+        self
+    }
+
+    /// Walk at most `limit` commits when counting `GIT_CONTRIBUTOR_COUNT`, to protect
+    /// against pathologically large histories. The default is unlimited.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub fn set_git_contributor_count_limit(&mut self, limit: usize) -> &mut Self {
+        self.git_contributor_count_limit = Some(limit);
+        self
+    }
+
+    /// Write the number of changed lines in the working tree and the index, compared to
+    /// HEAD, as `GIT_DIRTY_INSERTIONS` and `GIT_DIRTY_DELETIONS`.
+    ///
+    /// This diffs the contents of all changed files, which is more expensive than
+    /// determining `GIT_DIRTY`. The default is `false`.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub fn set_git_dirty_lines(&mut self, enabled: bool) -> &mut Self {
+        self.git_dirty_lines = enabled;
+        self
+    }
+
+    /// Write the paths of up to `max_files` files that differ from HEAD as `GIT_DIRTY_FILES`,
+    /// and whether there were more as `GIT_DIRTY_FILES_TRUNCATED`. Untracked and ignored
+    /// files are not considered.
+    ///
+    /// Notice that the file names end up in the binary. The default is to not write them.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub fn set_git_dirty_files(&mut self, max_files: usize) -> &mut Self {
+        self.git_dirty_files = Some(max_files);
+        self
+    }
+
+    /// Write `SOURCE_TREE_HASH`, a hash over the paths, modes and contents of all files in
+    /// HEAD's tree, like hashing the output of `git archive HEAD`.
+    ///
+    /// Unlike `GIT_COMMIT_HASH`, it does not change with the commit-message or branch-name,
+    /// which makes it a cache-key independent of the git-history. It is computed with the
+    /// algorithm set via `Options::set_hash_algorithm()`, and `None` in bare repositories
+    /// and shallow clones. Notice that reading every file is expensive for large
+    /// repositories. The default is `false`.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub fn set_source_tree_hash(&mut self, enabled: bool) -> &mut Self {
+        self.source_tree_hash = enabled;
+        self
+    }
+
+    /// Consider files ignored by `.gitignore` when determining `GIT_DIRTY`, e.g. stray
+    /// build-artifacts in the source-tree. The default is `false`.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub fn set_git_dirty_include_ignored(&mut self, enabled: bool) -> &mut Self {
+        self.git_dirty_include_ignored = enabled;
+        self
+    }
+
+    /// The kinds of changes which make the repository dirty when determining `GIT_DIRTY`.
+    ///
+    /// ```rust,no_run
+    /// use built::DirtyStatuses;
+    ///
+    /// let mut options = built::Options::default();
+    /// // Untracked files make the repository dirty, unstaged changes do not.
+    /// options.set_git_dirty_statuses(
+    ///     DirtyStatuses::INDEX | DirtyStatuses::TYPECHANGE | DirtyStatuses::UNTRACKED,
+    /// );
+    /// ```
+    ///
+    /// `Options::set_git_dirty_include_ignored()` adds `DirtyStatuses::IGNORED`. The default
+    /// is `DirtyStatuses::default()`, i.e. all changes to tracked files.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub fn set_git_dirty_statuses(&mut self, statuses: DirtyStatuses) -> &mut Self {
+        self.git_dirty_statuses = statuses;
+        self
+    }
+
+    /// Only consider the files matching `pathspecs` when determining `GIT_DIRTY`, e.g.
+    /// `src` to ignore changes to generated files elsewhere in the repository.
+    ///
+    /// The pathspecs are relative to the repository's root and may be globs, like for
+    /// `git status -- <pathspec>`; a directory matches all files below it. The default is to
+    /// consider all files.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub fn set_git_dirty_paths(&mut self, pathspecs: Vec<std::path::PathBuf>) -> &mut Self {
+        self.git_dirty_paths = pathspecs;
+        self
+    }
+
+    /// Fail with an error instead of writing the file if `GIT_DIRTY` is `true`, e.g. to
+    /// prevent releasing uncommitted changes. The build-script should abort if
+    /// `write_built_file_with_options()` returns an error. The default is `false`.
+    ///
+    /// This also applies to sources added via `Options::add_named_git_source()`, but not
+    /// if the collection is skipped via `Options::set_skip_in_ide()`.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub fn set_fail_if_dirty(&mut self, enabled: bool) -> &mut Self {
+        self.fail_if_dirty = enabled;
+        self
+    }
+
+    /// Verify the signature of HEAD's commit as `GIT_COMMIT_SIGNATURE_VERIFIED`.
+    ///
+    /// This runs `git verify-commit`, which requires `git` and e.g. `gpg` to be installed,
+    /// and checks the signature against the keyring of the build-machine. The result
+    /// therefore depends on the build-environment. The default is `false`.
+    ///
+    /// Only available if the `verify-signature`-feature is enabled.
+    #[cfg(all(feature = "verify-signature", any(feature = "git2", feature = "gix")))]
+    pub fn set_git_verify_commit_signature(&mut self, enabled: bool) -> &mut Self {
+        self.git_verify_commit_signature = enabled;
+        self
+    }
+
+    /// Write the note attached to HEAD's commit as `GIT_COMMIT_NOTES`.
+    ///
+    /// Notice that `git clone` does not fetch notes by default, so the note may be missing
+    /// in some clones. The default is `false`.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub fn set_git_commit_notes(&mut self, enabled: bool) -> &mut Self {
+        self.git_commit_notes = enabled;
+        self
+    }
+
+    /// Describe `committish`, e.g. `origin/main`, as `GIT_VERSION` instead of HEAD.
+    ///
+    /// `GIT_VERSION` is `None` if `committish` can't be resolved.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub fn set_git_describe_committish(&mut self, committish: &str) -> &mut Self {
+        self.git_describe_committish = Some(committish.to_owned());
+        self
+    }
+
+    /// Write whether HEAD's commit is reachable from any remote-tracking branch as
+    /// `GIT_COMMIT_ON_REMOTE`, e.g. to tell whether a build can be reproduced by others.
+    ///
+    /// This requires walking the history and is therefore disabled by default.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub fn set_git_commit_on_remote(&mut self, enabled: bool) -> &mut Self {
+        self.git_commit_on_remote = enabled;
+        self
+    }
+
+    /// Write whether HEAD's commit is reachable from the local branch `branch`, e.g. `main`,
+    /// as `GIT_IS_ANCESTOR_OF_BASE`, e.g. to verify that a release was built from merged code.
+    ///
+    /// `GIT_IS_ANCESTOR_OF_BASE` is `None` if there is no such branch.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub fn set_git_base_branch(&mut self, branch: &str) -> &mut Self {
+        self.git_base_branch = Some(branch.to_owned());
+        self
+    }
+
+    /// Describe the commit against all references, like `git describe --all`, instead of only
+    /// against tags. This gives untagged repositories a `GIT_VERSION` like `heads/main-3-gabc1234`
+    /// instead of the bare short commit id. The default is `false`.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub fn set_git_describe_all(&mut self, enabled: bool) -> &mut Self {
+        self.git_describe_all = enabled;
+        self
+    }
+
+    /// Collect the information about a commit from `committish`, e.g. `v1.0.0`, instead of HEAD.
+    ///
+    /// This applies to all variables about the commit and its history, e.g. `GIT_COMMIT_HASH`,
+    /// `GIT_TAGS_AT_HEAD`, `GIT_NEAREST_TAG` and `GIT_COMMIT_SIGNING_KEY`, and, unless
+    /// `Options::set_git_describe_committish()` is used, to `GIT_VERSION`; these are `None` if
+    /// `committish` can't be resolved. The variables about the branch and the working tree,
+    /// e.g. `GIT_HEAD_REF`, `GIT_UPSTREAM_REMOTE` and `GIT_DIRTY`, still describe HEAD.
+    #[cfg(any(feature = "git2", feature = "gix"))]
+    pub fn set_git_committish(&mut self, committish: &str) -> &mut Self {
+        self.git_committish = Some(committish.to_owned());
+        self
+    }
+
+    /// Package information from `cargo metadata`, e.g. `PKG_EDITION`.
+    #[cfg(feature = "cargo-metadata")]
+    pub fn set_metadata(&mut self, enabled: bool) -> &mut Self {
+        self.metadata = enabled;
+        self
+    }
+
+    /// How `GIT_COMMIT_DATE` is rendered. The default is `TimeFormat::Original`.
+    #[cfg(all(feature = "chrono", any(feature = "git2", feature = "gix")))]
+    pub fn set_git_time_format(&mut self, format: TimeFormat) -> &mut Self {
+        self.git_time_format = format;
+        self
+    }
+
+    /// Dependencies as documented by `Cargo.lock`, e.g. `DEPENDENCIES`.
+    #[cfg(feature = "cargo-lock")]
+    pub fn set_dependencies(&mut self, enabled: bool) -> &mut Self {
+        self.dependencies = enabled;
+        self
+    }
+
+    /// Write the versions of the dependencies, e.g. in `DEPENDENCIES`. The default is `true`.
+    ///
+    /// If disabled, the dependencies are written as an array of names only, e.g.
+    /// `[&str; N]` instead of `[(&str, &str); N]`, which changes the types in the
+    /// generated file.
+    #[cfg(feature = "cargo-lock")]
+    pub fn set_dependency_versions(&mut self, enabled: bool) -> &mut Self {
+        self.dependency_versions = enabled;
+        self
+    }
+
+    /// Write at most `max` entries in `DEPENDENCIES`, and whether the list was cut as
+    /// `DEPENDENCIES_TRUNCATED`. The default is to write all dependencies.
+    ///
+    /// The dependencies are sorted by name and version before the first `max` are kept,
+    /// so the truncation is deterministic. Other lists, e.g. `DIRECT_DEPENDENCIES`, are
+    /// not affected.
+    #[cfg(feature = "cargo-lock")]
+    pub fn set_max_dependencies(&mut self, max: usize) -> &mut Self {
+        self.max_dependencies = Some(max);
+        self
+    }
+
+    /// Fail if `Cargo.lock` is out of date, as if the build was run with `cargo build --locked`.
+    ///
+    /// This runs `cargo metadata --locked`, which may require network-access to resolve the
+    /// dependencies. If `cargo` fails for any other reason than a stale `Cargo.lock`, e.g.
+    /// while offline, the check is skipped with a warning. The default is `false`.
+    #[cfg(feature = "cargo-lock")]
+    pub fn set_require_locked(&mut self, enabled: bool) -> &mut Self {
+        self.require_locked = enabled;
+        self
+    }
+
+    /// Write the dependencies as resolved by `cargo metadata` for the current target and the
+    /// enabled features, instead of all packages listed in `Cargo.lock`.
+    ///
+    /// Only normal dependencies are followed, so build- and dev-dependencies (including
+    /// `built` itself) don't show up, and neither do dependencies of other targets or of
+    /// features which are not enabled. This runs `cargo metadata`, which may require
+    /// network-access. The default is `false`.
+    #[cfg(all(feature = "cargo-lock", feature = "cargo-metadata"))]
+    pub fn set_dependencies_resolved(&mut self, enabled: bool) -> &mut Self {
+        self.dependencies_resolved = enabled;
+        self
+    }
+
+    /// The `cargo`-binary used to run `cargo metadata`, e.g. in sandboxed builds or with
+    /// custom toolchains.
+    ///
+    /// The default is the binary given by the `CARGO` environment variable, which `cargo`
+    /// sets for build-scripts, falling back to `cargo` on the `PATH`.
+    #[cfg(any(feature = "cargo-lock", feature = "cargo-metadata"))]
+    pub fn set_cargo_path(&mut self, path: impl Into<std::path::PathBuf>) -> &mut Self {
+        self.cargo_path = Some(path.into());
+        self
+    }
+
+    /// The build-time, e.g. `BUILT_TIME_UTC`.
+    #[cfg(feature = "chrono")]
+    pub fn set_time(&mut self, enabled: bool) -> &mut Self {
+        self.time = enabled;
+        self
+    }
+
+    /// Write the components of the build-time as `BUILT_YEAR`, `BUILT_MONTH`, `BUILT_DAY`
+    /// and `BUILT_ISO_WEEK`, e.g. for calendar-versioning.
+    ///
+    /// They are derived from `BUILT_TIME_UTC`, so `SOURCE_DATE_EPOCH` and the override of
+    /// `BUILT_TIME_UTC` apply. Only written if the build-time is written as well. The
+    /// default is `false`.
+    #[cfg(feature = "chrono")]
+    pub fn set_time_components(&mut self, enabled: bool) -> &mut Self {
+        self.time_components = enabled;
+        self
+    }
+
+    /// Write the build-time in the local timezone of the build-machine as `BUILT_TIME_LOCAL`.
+    ///
+    /// The timezone differs between machines, so the time is written in UTC if
+    /// `SOURCE_DATE_EPOCH` is set. It is `None` if the timezone can't be determined. Only
+    /// written if the build-time is written as well. The default is `false`.
+    #[cfg(feature = "chrono")]
+    pub fn set_time_local(&mut self, enabled: bool) -> &mut Self {
+        self.time_local = enabled;
+        self
+    }
+
+    /// Information about `built` itself, i.e. `BUILT_IN_IDE`, `BUILD_FINGERPRINT` and
+    /// `OVERRIDE_VARIABLES_USED`.
+    pub fn set_built_meta(&mut self, enabled: bool) -> &mut Self {
+        self.built_meta = enabled;
+        self
+    }
+
+    /// Write `BUILT_COLLECTION_DURATION_MS`, the time `built` took to collect its information.
+    ///
+    /// The duration differs from build to build, so it is never written if
+    /// `SOURCE_DATE_EPOCH` is set. The default is `false`.
+    pub fn set_collection_duration(&mut self, enabled: bool) -> &mut Self {
+        self.collection_duration = enabled;
+        self
+    }
+
+    /// Register the inputs `built` reads, e.g. `Cargo.toml`, `.git/HEAD` and `Cargo.lock`, via
+    /// `cargo::rerun-if-changed` and record them as `BUILT_RERUN_PATHS`.
+    ///
+    /// Notice that this disables Cargo's default of re-running the build-script if any
+    /// file in the package changes. Cargo does not tell the build-script *why* it was
+    /// re-run, so there is no way to record the path which triggered a re-run.
+    pub fn set_rerun_if_changed(&mut self, enabled: bool) -> &mut Self {
+        self.rerun_if_changed = enabled;
+        self
+    }
+
+    /// Write string-literals that appear in more than one place, e.g. the target-triple in
+    /// `TARGET` and `HOST`, only once, as a `const` the variables refer to.
+    ///
+    /// The default output is simpler to read. Notice that `rustc` already merges identical
+    /// string-literals: For a binary using `DEPENDENCIES`, `TARGET`, `HOST` and others, the
+    /// size of the final executable was unchanged in both debug- and release-builds. The
+    /// generated file itself is smaller if there are many dependencies.
+    pub fn set_intern_strings(&mut self, enabled: bool) -> &mut Self {
+        self.intern_strings = enabled;
+        self
+    }
+
+    /// Write optional variables whose value is known during compilation as their
+    /// concrete type, e.g. `GIT_COMMIT_HASH` as `&str` instead of `Option<&str>`.
+    ///
+    /// Notice that this changes the public types in the generated file depending on the
+    /// build-environment, e.g. whether a git-repository was found. Variables whose value is
+    /// absent are still written as `None`. The default is `false`.
+    pub fn set_unwrap_known_values(&mut self, enabled: bool) -> &mut Self {
+        self.unwrap_known_values = enabled;
+        self
+    }
+
+    /// Write variables of type `&str` and `Option<&str>` as `Cow<'static, str>` and
+    /// `Option<Cow<'static, str>>` instead, e.g. to replace them by an owned value in tests.
+    /// Cloning the borrowed values does not allocate.
+    ///
+    /// Arrays and tuples of strings, e.g. `DEPENDENCIES`, are unchanged. Notice that this
+    /// changes the public types in the generated file, which then requires `std`. The default
+    /// is `false`.
+    pub fn set_cow_strings(&mut self, enabled: bool) -> &mut Self {
+        self.cow_strings = enabled;
+        self
+    }
+
+    /// Write a function `print_build_info()`, which prints every variable in the generated
+    /// file to stdout, e.g. for a hidden `--build-info` subcommand.
+    ///
+    /// Each variable is printed on its own line as `NAME: value`, where `value` is
+    /// formatted via `Debug`, in the order the variables are written. The function requires
+    /// `std`. The default is `false`.
+    pub fn set_emit_print_fn(&mut self, enabled: bool) -> &mut Self {
+        self.emit_print_fn = enabled;
+        self
+    }
+
+    /// Write `BUILT_MAP`, a `&[(&str, &str)]` of every variable's name and plain value,
+    /// e.g. to look up variables by name at runtime.
+    ///
+    /// The values are the same as those of [`Options::collect_map()`]: Strings are
+    /// unescaped, `Some(..)` is unwrapped and arrays are joined like the `_STR`-variables.
+    /// Variables whose value is `None` are left out of the map. The default is `false`.
+    pub fn set_emit_map(&mut self, enabled: bool) -> &mut Self {
+        self.emit_map = enabled;
+        self
+    }
+
+    /// Write a macro `build_info!`, which expands to the value of a variable given its name
+    /// in lowercase, e.g. `build_info!(pkg_version)` instead of `built_info::PKG_VERSION`.
+    ///
+    /// The macro has an arm for exactly the variables in the generated file. To use it outside
+    /// of the module that includes the generated file, put `#[macro_use]` on that module, e.g.
+    /// `#[macro_use] mod built_info { .. }`. The default is `false`.
+    pub fn set_emit_macro(&mut self, enabled: bool) -> &mut Self {
+        self.emit_macro = enabled;
+        self
+    }
+
+    /// Write the generated file without doc comments and indentation, which makes it
+    /// considerably smaller and faster to compile, e.g. if many dependencies or cfgs are
+    /// written.
+    ///
+    /// The variables and their values are unchanged, and the file can still be read via
+    /// [`parse_built_file()`](crate::parse_built_file). Notice that the variables are then
+    /// undocumented, which trips `missing_docs` if the module including the file is
+    /// public. The default is `false`.
+    pub fn set_compact(&mut self, enabled: bool) -> &mut Self {
+        self.compact = enabled;
+        self
+    }
+
+    /// Skip the expensive collection of git-information and `cargo metadata` if the
+    /// build-script is run by an IDE, i.e. `rust-analyzer`, writing `None` or empty values
+    /// instead. This keeps the editor responsive; actual builds collect everything.
+    ///
+    /// `rust-analyzer` is detected by the `RUST_ANALYZER_INTERNALS_DO_NOT_USE` and
+    /// `RA_RUSTC_WRAPPER`-variables it sets, or by a `RUSTC_WRAPPER` named `rust-analyzer`;
+    /// see `BUILT_IN_IDE`. The default is `false`.
+    pub fn set_skip_in_ide(&mut self, enabled: bool) -> &mut Self {
+        self.skip_in_ide = enabled;
+        self
+    }
+
+    /// Write `channel` verbatim as `BUILD_CHANNEL`, e.g. `nightly` or `canary`.
+    pub fn set_build_channel(&mut self, channel: &str) -> &mut Self {
+        self.build_channel = Some(BuildChannel::Verbatim(channel.to_owned()));
+        self
+    }
+
+    /// Write the value of the environment-variable `var` as `BUILD_CHANNEL`, e.g. as set
+    /// by the CI-pipeline; an empty string if `var` is not set.
+    pub fn set_build_channel_from_env(&mut self, var: &str) -> &mut Self {
+        self.build_channel = Some(BuildChannel::Env(var.to_owned()));
+        self
+    }
+
+    /// Write the operating system of the machine that built the crate, as `BUILD_OS` and
+    /// `BUILD_OS_VERSION`, and its architecture and number of CPUs, as `BUILD_ARCH` and
+    /// `BUILD_CPU_COUNT`; this is not necessarily the target.
+    ///
+    /// The version is queried from `/etc/os-release`, `sw_vers` or `ver`, and may be
+    /// `None`. Notice that this embeds information about the build-environment into the
+    /// binary; `BUILD_CPU_COUNT` in particular differs between otherwise identical builds.
+    /// The default is `false`.
+    pub fn set_build_os(&mut self, enabled: bool) -> &mut Self {
+        self.build_os = enabled;
+        self
+    }
+
+    /// Write the sysroot of the compiler, i.e. the output of `rustc --print sysroot`, as
+    /// `RUSTC_SYSROOT`.
+    ///
+    /// Notice that this embeds a path of the build-machine into the binary, which makes the
+    /// build less reproducible. The default is `false`.
+    pub fn set_rustc_sysroot(&mut self, enabled: bool) -> &mut Self {
+        self.rustc_sysroot = enabled;
+        self
+    }
+
+    /// The algorithm used for hashes `built` computes itself, i.e. `BUILD_FINGERPRINT` and
+    /// `SOURCE_TREE_HASH`.
+    ///
+    /// The default is `HashAlgorithm::Sha256` if the `sha2`-feature is enabled, and
+    /// `HashAlgorithm::Fnv1a` otherwise, which requires no dependencies but is not a
+    /// cryptographic hash. The other algorithms are available via the feature-flags of the
+    /// same name: `sha2` for `HashAlgorithm::Sha256`, `sha1` for `HashAlgorithm::Sha1` and
+    /// `blake3` for `HashAlgorithm::Blake3`.
+    pub fn set_hash_algorithm(&mut self, algorithm: HashAlgorithm) -> &mut Self {
+        self.hash_algorithm = algorithm;
+        self
+    }
+
+    /// Additionally write the variable `name` of type `datatype`, e.g. `&str`, with the
+    /// Rust-expression `value`, e.g. `"foo"` (including the quotes), documented by `doc`.
+    ///
+    /// This embeds information `built` does not collect itself. The variable is always
+    /// written, even if the selection of [`Options::minimal()`] does not include it. May be
+    /// called multiple times. If `name` clashes with a variable `built` writes itself, or with
+    /// `BUILT_MAP`, `print_build_info` or `build_info` if those are written, writing the file
+    /// fails with an error naming the variable.
+    ///
+    /// ```rust,no_run
+    /// let mut options = built::Options::default();
+    /// options.add_variable("BUILD_HOST", "&str", "\"ci-runner-7\"", "The machine that built us.");
+    /// ```
+    ///
+    /// # Panics
+    /// If `name` is not a valid identifier or was already added, or if `datatype` or `value`
+    /// are empty.
+    pub fn add_variable(
+        &mut self,
+        name: &str,
+        datatype: &str,
+        value: &str,
+        doc: &str,
+    ) -> &mut Self {
+        assert!(
+            name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'),
+            "The name of a variable must be a valid identifier, got `{name}`"
+        );
+        assert!(
+            self.custom_variables.iter().all(|var| var.name != name),
+            "The variable `{name}` was already added"
+        );
+        assert!(
+            !datatype.trim().is_empty() && !value.trim().is_empty(),
+            "The type and value of the variable `{name}` must not be empty"
+        );
+        self.custom_variables.push(crate::Variable {
+            name: name.to_owned(),
+            datatype: datatype.to_owned(),
+            value: value.to_owned(),
+            doc: doc.to_owned(),
+        });
+        self
+    }
+
+    /// Apply `f` to these options only if `feature` of the crate being built is enabled,
+    /// e.g. to collect expensive information only if the crate's own feature asks for it.
+    ///
+    /// Cargo tells the build-script about enabled features via `CARGO_FEATURE_<name>`, with
+    /// `name` in uppercase and `-` replaced by `_`; therefore `my-feature` and `my_feature`
+    /// can't be distinguished. Cargo re-runs the build-script if the features change.
+    ///
+    /// ```rust,no_run
+    /// let mut options = built::Options::default();
+    /// # #[cfg(any(feature = "git2", feature = "gix"))]
+    /// options.enable_if_feature("build-stats", |o| {
+    ///     o.set_git_contributor_count(true);
+    /// });
+    /// ```
+    pub fn enable_if_feature(&mut self, feature: &str, f: impl FnOnce(&mut Self)) -> &mut Self {
+        let var = format!("CARGO_FEATURE_{}", feature.to_uppercase().replace('-', "_"));
+        if std::env::var_os(var).is_some() {
+            f(self);
+        }
+        self
+    }
+
+    /// Collects the information selected by these options about the crate at
+    /// `CARGO_MANIFEST_DIR`, without writing any file.
+    ///
+    /// Every variable is mapped to its plain value, as written by `write_built_env()`;
+    /// `None` if an optional value is absent. No `cargo::`-directives are printed, as this
+    /// is not meant for build-scripts. The guardrails like `Options::set_fail_if_dirty()`
+    /// apply just as they would when writing the file, so this can also serve as a precheck
+    /// in CI.
+    ///
+    /// ```rust,no_run
+    /// for (name, value) in built::Options::default().collect_map().unwrap() {
+    ///     println!("{name}: {}", value.as_deref().unwrap_or("-"));
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    /// Same as `write_built_file_with_opts()`.
+    pub fn collect_map(
+        &self,
+    ) -> std::io::Result<std::collections::BTreeMap<String, Option<String>>> {
+        #[cfg(any(feature = "cargo-lock", feature = "git2", feature = "gix"))]
+        let manifest_location =
+            std::env::var_os("CARGO_MANIFEST_DIR").map(std::path::PathBuf::from);
+        let mut built_file = crate::BuiltWriter::quiet(self);
+        crate::write_variables(
+            self,
+            #[cfg(any(feature = "cargo-lock", feature = "git2", feature = "gix"))]
+            manifest_location.as_deref(),
+            &mut built_file,
+        )?;
+        Ok(built_file.into_map())
+    }
+
+    /// Validates that the information selected by these options can be collected, e.g. as a
+    /// precheck in CI, without writing any file.
+    ///
+    /// This is the same as `Options::collect_map()`: no `cargo::`-directives are printed, and
+    /// the guardrails like `Options::set_fail_if_dirty()` apply just as they would when
+    /// writing the file.
+    ///
+    /// ```rust,no_run
+    /// let mut options = built::Options::default();
+    /// # #[cfg(any(feature = "git2", feature = "gix"))]
+    /// options.set_fail_if_dirty(true);
+    /// if let Err(e) = options.dry_run() {
+    ///     eprintln!("`built` would fail: {e}");
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    /// Same as `write_built_file_with_opts()`.
+    pub fn dry_run(&self) -> std::io::Result<std::collections::BTreeMap<String, Option<String>>> {
+        self.collect_map()
+    }
+
+    /// Like `Options::collect_map()`, but runs the collection on `tokio`'s pool for blocking
+    /// tasks, so long-running git-operations do not block the async executor.
+    ///
+    /// This is meant for tools which use `built` as a library, not for build-scripts, which
+    /// should simply call `write_built_file()`. Must be called from within a `tokio`-runtime.
+    ///
+    /// ```rust,no_run
+    /// # async fn f() {
+    /// let map = built::Options::default().collect_map_async().await.unwrap();
+    /// println!("{:?}", map["PKG_VERSION"]);
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Same as `Options::collect_map()`.
+    ///
+    /// # Panics
+    /// If called outside of a `tokio`-runtime, or if the collection panics.
+    #[cfg(feature = "tokio")]
+    pub async fn collect_map_async(
+        &self,
+    ) -> std::io::Result<std::collections::BTreeMap<String, Option<String>>> {
+        let options = self.clone();
+        match tokio::task::spawn_blocking(move || options.collect_map()).await {
+            Ok(result) => result,
+            Err(e) => std::panic::resume_unwind(e.into_panic()),
+        }
+    }
+
+    /// The `cargo`-binary to run; see `Options::set_cargo_path()`.
+    #[cfg(any(feature = "cargo-lock", feature = "cargo-metadata"))]
+    pub(crate) fn cargo(&self, envmap: &crate::environment::EnvironmentMap) -> std::path::PathBuf {
+        self.cargo_path
+            .clone()
+            .or_else(|| envmap.get("CARGO").map(Into::into))
+            .unwrap_or_else(|| "cargo".into())
+    }
+
+    /// Whether the variable `name` is written at all.
+    pub(crate) fn emits(&self, name: &str) -> bool {
+        self.variables.is_none_or(|v| v.contains(&name))
+    }
+}
+
+/// Where `BUILD_CHANNEL` comes from.
+#[derive(Debug, Clone)]
+pub(crate) enum BuildChannel {
+    Verbatim(String),
+    Env(String),
+}
+
+/// The kinds of changes which make the repository dirty; see `Options::set_git_dirty_statuses()`.
+///
+/// Kinds are combined using `|`, e.g. `DirtyStatuses::INDEX | DirtyStatuses::UNTRACKED`.
+#[cfg(any(feature = "git2", feature = "gix"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DirtyStatuses(u8);
+
+#[cfg(any(feature = "git2", feature = "gix"))]
+impl DirtyStatuses {
+    /// Changes staged in the index, i.e. new, modified, deleted and renamed files, and
+    /// unresolved merge-conflicts.
+    pub const INDEX: Self = Self(1);
+    /// Changes to tracked files in the working tree which are not staged, i.e. modified and
+    /// deleted files, and unresolved merge-conflicts.
+    pub const WORKTREE: Self = Self(1 << 1);
+    /// Changes to the type of a tracked file, e.g. from a regular file to a symlink, staged
+    /// or not.
+    pub const TYPECHANGE: Self = Self(1 << 2);
+    /// Files in the working tree which are neither tracked nor ignored.
+    pub const UNTRACKED: Self = Self(1 << 3);
+    /// Files in the working tree which are ignored, e.g. by `.gitignore`.
+    pub const IGNORED: Self = Self(1 << 4);
+
+    /// No kind of change, i.e. the repository is never dirty.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Whether all kinds of changes in `other` are also in `self`.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Whether any kind of change in `other` is also in `self`.
+    #[cfg(feature = "gix")]
+    pub(crate) const fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+#[cfg(any(feature = "git2", feature = "gix"))]
+impl Default for DirtyStatuses {
+    /// `INDEX | WORKTREE | TYPECHANGE`, i.e. all changes to tracked files.
+    fn default() -> Self {
+        Self::INDEX | Self::WORKTREE | Self::TYPECHANGE
+    }
+}
+
+#[cfg(any(feature = "git2", feature = "gix"))]
+impl std::ops::BitOr for DirtyStatuses {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// The algorithm used for hashes `built` computes itself; see `Options::set_hash_algorithm()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HashAlgorithm {
+    /// The 64-bit FNV-1a hash; not a cryptographic hash.
+    Fnv1a,
+    /// SHA-256; requires the `sha2`-feature.
+    #[cfg(feature = "sha2")]
+    Sha256,
+    /// SHA-1; requires the `sha1`-feature.
+    #[cfg(feature = "sha1")]
+    Sha1,
+    /// BLAKE3; requires the `blake3`-feature.
+    #[cfg(feature = "blake3")]
+    Blake3,
+}
+
+impl Default for HashAlgorithm {
+    /// `HashAlgorithm::Sha256` if the `sha2`-feature is enabled, `HashAlgorithm::Fnv1a`
+    /// otherwise.
+    fn default() -> Self {
+        #[cfg(feature = "sha2")]
+        return HashAlgorithm::Sha256;
+        #[cfg(not(feature = "sha2"))]
+        return HashAlgorithm::Fnv1a;
+    }
+}
+
+impl HashAlgorithm {
+    /// Hashes `data`, returning the digest as lowercase hex.
+    pub(crate) fn hex_digest(self, data: &[u8]) -> String {
+        #[cfg(any(feature = "sha2", feature = "sha1", feature = "blake3"))]
+        fn hex(digest: &[u8]) -> String {
+            digest.iter().map(|b| format!("{b:02x}")).collect()
+        }
+        match self {
+            HashAlgorithm::Fnv1a => format!("{:016x}", crate::util::fnv1a_64(data)),
+            #[cfg(feature = "sha2")]
+            HashAlgorithm::Sha256 => hex(&<sha2::Sha256 as sha2::Digest>::digest(data)),
+            #[cfg(feature = "sha1")]
+            HashAlgorithm::Sha1 => hex(&<sha1::Sha1 as sha1::Digest>::digest(data)),
+            #[cfg(feature = "blake3")]
+            HashAlgorithm::Blake3 => hex(blake3::hash(data).as_bytes()),
+        }
+    }
+}
+
+/// How a point in time, e.g. `GIT_COMMIT_DATE`, is rendered.
+#[cfg(all(feature = "chrono", any(feature = "git2", feature = "gix")))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimeFormat {
+    /// RFC2822, in the timezone-offset the time was originally recorded in.
+    Original,
+    /// RFC2822, in UTC.
+    Utc,
+    /// A `strftime`-like format as understood by `chrono::format::strftime`, in the
+    /// timezone-offset the time was originally recorded in.
+    Custom(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "valid identifier")]
+    fn add_variable_invalid_name() {
+        Options::default().add_variable("BUILD-HOST", "&str", "\"foo\"", "");
+    }
+
+    #[test]
+    #[should_panic(expected = "already added")]
+    fn add_variable_duplicate() {
+        Options::default()
+            .add_variable("BUILD_HOST", "&str", "\"foo\"", "")
+            .add_variable("BUILD_HOST", "&str", "\"bar\"", "");
+    }
+
+    #[test]
+    fn generated_names_are_reserved() {
+        let write = |options: &Options, name: &str| {
+            crate::BuiltWriter::new(options).write_variable(
+                name,
+                format_args!("&str"),
+                format_args!("\"foo\""),
+                format_args!(""),
+            )
+        };
+        let mut options = Options::minimal();
+        assert!(write(&options, "BUILT_MAP").is_ok());
+        options
+            .set_emit_map(true)
+            .set_emit_print_fn(true)
+            .set_emit_macro(true);
+        for name in ["BUILT_MAP", "print_build_info", "build_info"] {
+            let err = write(&options, name).unwrap_err();
+            assert!(err.to_string().contains("is written more than once"));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "must not be empty")]
+    fn add_variable_empty_value() {
+        Options::default().add_variable("BUILD_HOST", "&str", " ", "");
+    }
+
+    #[test]
+    #[cfg(all(feature = "tokio", any(feature = "git2", feature = "gix")))]
+    fn collect_map_async() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let options = Options::git_only();
+        let map = runtime.block_on(options.collect_map_async()).unwrap();
+        assert_eq!(map, options.collect_map().unwrap());
+    }
+
+    #[test]
+    fn test_hex_digest() {
+        assert_eq!(HashAlgorithm::Fnv1a.hex_digest(b""), "cbf29ce484222325");
+        assert_eq!(HashAlgorithm::Fnv1a.hex_digest(b"abc"), "e71fa2190541574b");
+        #[cfg(feature = "sha2")]
+        assert_eq!(
+            HashAlgorithm::Sha256.hex_digest(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        #[cfg(feature = "sha1")]
+        assert_eq!(
+            HashAlgorithm::Sha1.hex_digest(b"abc"),
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+        #[cfg(feature = "blake3")]
+        assert_eq!(
+            HashAlgorithm::Blake3.hex_digest(b"abc"),
+            "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+        );
+    }
+
+    #[test]
+    fn test_default_hash_algorithm() {
+        #[cfg(feature = "sha2")]
+        assert_eq!(HashAlgorithm::default(), HashAlgorithm::Sha256);
+        #[cfg(not(feature = "sha2"))]
+        assert_eq!(HashAlgorithm::default(), HashAlgorithm::Fnv1a);
+        assert_eq!(
+            Options::minimal().hash_algorithm,
+            Options::default().hash_algorithm
+        );
+    }
+}
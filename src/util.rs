@@ -92,7 +92,7 @@ macro_rules! parsefromenv_impl {
         )+
     };
 }
-parsefromenv_impl!(i64, i32, i16, i8, u64, u32, u16, u8, bool, String);
+parsefromenv_impl!(i64, i32, i16, i8, u64, u32, u16, u8, usize, bool, String);
 
 impl<'a, T> ParseFromEnv<'a> for Vec<T>
 where
@@ -142,10 +142,8 @@ where
     }
 }
 
-#[cfg(feature = "cargo-lock")]
 pub(crate) struct TupleArrayDisplay<'a, T>(pub &'a [(T, T)]);
 
-#[cfg(feature = "cargo-lock")]
 impl<T> fmt::Display for TupleArrayDisplay<'_, T>
 where
     T: AsRef<str>,
@@ -164,6 +162,160 @@ where
     }
 }
 
+/// Calls `f` for every string-literal in `value`, including the quotes, replacing the
+/// literal with the result of `f` if it is `Some`.
+pub(crate) fn replace_str_literals(
+    value: &str,
+    mut f: impl FnMut(&str) -> Option<String>,
+) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('"') {
+        result.push_str(&rest[..start]);
+        let mut escaped = false;
+        let Some(len) = rest[start + 1..].find(|c| {
+            let end = !escaped && c == '"';
+            escaped = !escaped && c == '\\';
+            end
+        }) else {
+            break;
+        };
+        let literal = &rest[start..start + len + 2];
+        result.push_str(&f(literal).unwrap_or_else(|| literal.to_owned()));
+        rest = &rest[start + len + 2..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Creates the file `dst` with the content written by `f`, such that `dst` is either
+/// left untouched or replaced by the complete content, but never truncated.
+///
+/// The content is written to a temporary file in the same directory, which is then
+/// renamed over `dst`. If `dst` already has the exact same content, it is not touched at
+/// all, so its modification time does not cause needless recompilation.
+pub(crate) fn write_file_atomically(
+    dst: &std::path::Path,
+    f: impl FnOnce(&mut Vec<u8>) -> std::io::Result<()>,
+) -> std::io::Result<()> {
+    let mut content = Vec::new();
+    f(&mut content)?;
+    if std::fs::read(dst).is_ok_and(|existing| existing == content) {
+        return Ok(());
+    }
+
+    let mut tmp_name = std::ffi::OsString::from(".");
+    tmp_name.push(dst.file_name().unwrap_or_default());
+    tmp_name.push(format!(".{}.tmp", std::process::id()));
+    let tmp = dst.with_file_name(tmp_name);
+    let result = std::fs::File::create(&tmp).and_then(|mut file| {
+        std::io::Write::write_all(&mut file, &content)?;
+        file.sync_all()?;
+        std::fs::rename(&tmp, dst)
+    });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Computes the 64-bit FNV-1a hash of `data`, which is stable across platforms and
+/// versions of Rust, unlike `std::hash::DefaultHasher`.
+pub(crate) fn fnv1a_64(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Rewrites a variable of type `&str` or `Option<&str>` to use `Cow<'static, str>` instead,
+/// returning its new type and value; `None` for all other types.
+pub(crate) fn cow_str(datatype: &str, value: &str) -> Option<(String, String)> {
+    const COW: &str = "::std::borrow::Cow<'static, str>";
+    const BORROWED: &str = "::std::borrow::Cow::Borrowed";
+    match datatype {
+        "&str" => Some((COW.to_owned(), format!("{BORROWED}({value})"))),
+        "Option<&str>" => Some((
+            format!("Option<{COW}>"),
+            match value
+                .strip_prefix("Some(")
+                .and_then(|v| v.strip_suffix(')'))
+            {
+                Some(inner) => format!("Some({BORROWED}({inner}))"),
+                None => value.to_owned(),
+            },
+        )),
+        _ => None,
+    }
+}
+
+/// Converts a Rust-literal, as written to the generated file, into its plain value.
+///
+/// String-literals are unescaped and `Some(..)` is unwrapped; `None` yields `None`.
+/// Arrays are joined by `", "` and tuples by `" "`, like the `_STR`-variables.
+pub(crate) fn plain_value(literal: &str) -> Option<String> {
+    parse_plain_value(&mut literal.trim().chars().peekable())
+}
+
+fn parse_plain_value(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> Option<String> {
+    match *chars.peek()? {
+        '"' => {
+            chars.next();
+            let mut s = String::new();
+            loop {
+                match chars.next()? {
+                    '"' => break,
+                    '\\' => s.push(match chars.next()? {
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        '0' => '\0',
+                        'u' => {
+                            let hex = chars
+                                .by_ref()
+                                .skip_while(|c| *c == '{')
+                                .take_while(|c| *c != '}')
+                                .collect::<String>();
+                            char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?
+                        }
+                        c => c,
+                    }),
+                    c => s.push(c),
+                }
+            }
+            Some(s)
+        }
+        open @ ('[' | '(') => {
+            chars.next();
+            let (close, sep) = if open == '[' { (']', ", ") } else { (')', " ") };
+            let mut items = Vec::new();
+            loop {
+                while chars.next_if(|c| c.is_whitespace() || *c == ',').is_some() {}
+                if chars.next_if_eq(&close).is_some() {
+                    break;
+                }
+                items.push(parse_plain_value(chars).unwrap_or_default());
+            }
+            Some(items.join(sep))
+        }
+        _ => {
+            let mut word = String::new();
+            while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || "_-.".contains(*c)) {
+                word.push(c);
+            }
+            match word.as_str() {
+                "" | "None" => None,
+                "Some" => {
+                    chars.next_if_eq(&'(')?;
+                    let value = parse_plain_value(chars);
+                    chars.next_if_eq(&')')?;
+                    value
+                }
+                _ => Some(word),
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -216,4 +368,111 @@ mod tests {
             Ok(Some(vec![123u32, 456u32]))
         );
     }
+
+    #[test]
+    fn test_plain_value() {
+        assert_eq!(plain_value(r#""foo""#), Some("foo".to_owned()));
+        assert_eq!(
+            plain_value(&format!("\"{}\"", "a \"b\"\n\u{e9}".escape_default())),
+            Some("a \"b\"\n\u{e9}".to_owned())
+        );
+        assert_eq!(plain_value("None"), None);
+        assert_eq!(plain_value(r#"Some("foo")"#), Some("foo".to_owned()));
+        assert_eq!(plain_value("Some(true)"), Some("true".to_owned()));
+        assert_eq!(plain_value("-12"), Some("-12".to_owned()));
+        assert_eq!(plain_value("[]"), Some(String::new()));
+        assert_eq!(plain_value(r#"["a", "b, c"]"#), Some("a, b, c".to_owned()));
+        assert_eq!(
+            plain_value(r#"[("foo", "0.1.0"), ("bar", "1.0.0")]"#),
+            Some("foo 0.1.0, bar 1.0.0".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_cow_str() {
+        assert_eq!(
+            cow_str("&str", r#""foo""#),
+            Some((
+                "::std::borrow::Cow<'static, str>".to_owned(),
+                r#"::std::borrow::Cow::Borrowed("foo")"#.to_owned()
+            ))
+        );
+        assert_eq!(
+            cow_str("Option<&str>", r#"Some("foo")"#).unwrap().1,
+            r#"Some(::std::borrow::Cow::Borrowed("foo"))"#
+        );
+        assert_eq!(cow_str("Option<&str>", "None").unwrap().1, "None");
+        assert_eq!(cow_str("[&str; 1]", r#"["foo"]"#), None);
+        assert_eq!(cow_str("bool", "true"), None);
+    }
+
+    #[test]
+    fn test_replace_str_literals() {
+        let value = r#"[("foo", "1.0"), ("b\"ar", "1.0")]"#;
+        let mut literals = Vec::new();
+        assert_eq!(
+            replace_str_literals(value, |l| {
+                literals.push(l.to_owned());
+                None
+            }),
+            value
+        );
+        assert_eq!(literals, [r#""foo""#, r#""1.0""#, r#""b\"ar""#, r#""1.0""#]);
+        assert_eq!(
+            replace_str_literals(value, |l| (l == r#""1.0""#).then(|| "V".to_owned())),
+            r#"[("foo", V), ("b\"ar", V)]"#
+        );
+        assert_eq!(replace_str_literals("Some(true)", |_| None), "Some(true)");
+    }
+
+    #[test]
+    fn test_write_file_atomically() {
+        use std::io::Write as _;
+
+        let dir = tempfile::tempdir().unwrap();
+        let dst = dir.path().join("built.rs");
+
+        write_file_atomically(&dst, |w| w.write_all(b"complete")).unwrap();
+        assert_eq!(std::fs::read_to_string(&dst).unwrap(), "complete");
+
+        let err = write_file_atomically(&dst, |w| {
+            w.write_all(b"partial")?;
+            Err(std::io::Error::other("interrupted"))
+        })
+        .unwrap_err();
+        assert_eq!(err.to_string(), "interrupted");
+        assert_eq!(std::fs::read_to_string(&dst).unwrap(), "complete");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_write_file_atomically_unchanged() {
+        use std::io::Write as _;
+
+        let dir = tempfile::tempdir().unwrap();
+        let dst = dir.path().join("built.rs");
+        write_file_atomically(&dst, |w| w.write_all(b"content")).unwrap();
+
+        let past = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1 << 30);
+        std::fs::File::options()
+            .write(true)
+            .open(&dst)
+            .unwrap()
+            .set_modified(past)
+            .unwrap();
+
+        write_file_atomically(&dst, |w| w.write_all(b"content")).unwrap();
+        assert_eq!(std::fs::metadata(&dst).unwrap().modified().unwrap(), past);
+
+        write_file_atomically(&dst, |w| w.write_all(b"changed")).unwrap();
+        assert_eq!(std::fs::read_to_string(&dst).unwrap(), "changed");
+        assert_ne!(std::fs::metadata(&dst).unwrap().modified().unwrap(), past);
+    }
+
+    #[test]
+    fn test_fnv1a_64() {
+        assert_eq!(fnv1a_64(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a_64(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a_64(b"foobar"), 0x8594_4171_f739_67e8);
+    }
 }
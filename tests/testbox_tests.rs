@@ -273,6 +273,7 @@ fn minimal_testbox() {
 [package]
 name = "minimal_testbox"
 version = "1.2.3-rc1"
+rust-version = "1.70"
 authors = ["Joe", "Bob"]
 build = "build.rs"
 description = "xobtset"
@@ -335,9 +336,18 @@ fn main() {{
     assert_eq!(built_info::PKG_HOMEPAGE, "localhost");
     assert_eq!(built_info::PKG_LICENSE, "MIT");
     assert_eq!(built_info::PKG_REPOSITORY, "https://dev.example.com/sources/testbox/");
+    assert_eq!(built_info::PKG_SOURCE, None);
+    assert_eq!(built_info::PKG_RUST_VERSION, Some("1.70"));
+    assert_eq!(built_info::PKG_RUST_VERSION_MAJOR, Some(1));
+    assert_eq!(built_info::PKG_RUST_VERSION_MINOR, Some(70));
+    assert_eq!(built_info::PKG_RUST_VERSION_PATCH, Some(0));
     assert!(built_info::NUM_JOBS > 0);
     assert!(built_info::OPT_LEVEL == "0");
     assert!(built_info::DEBUG);
+    assert!(built_info::DEBUG_ASSERTIONS);
+    assert_eq!(built_info::CFG_PANIC, "unwind");
+    assert!(built_info::CFG_TARGET_HAS_ATOMIC.contains(&"ptr"));
+    assert_eq!(built_info::BUILD_FINGERPRINT.len(), 16);
     assert_eq!(built_info::PROFILE, "debug");
     assert_eq!(built_info::FEATURES, {features});
     assert_eq!(built_info::FEATURES_STR, {features_str});
@@ -345,6 +355,7 @@ fn main() {{
                ["default", "megaawesome", "superawesome"]);
     assert_eq!(built_info::FEATURES_LOWERCASE_STR,
                "default, megaawesome, superawesome");
+    assert_eq!(built_info::FEATURES_BANNER, "default megaawesome superawesome");
     assert_ne!(built_info::RUSTC_VERSION, "");
     assert_ne!(built_info::RUSTDOC_VERSION, "");
     assert_ne!(built_info::HOST, "");
@@ -358,6 +369,7 @@ fn main() {{
     assert_ne!(built_info::CFG_POINTER_WIDTH, "");
     // For CFG_ENV, empty string is a possible value.
     let _: &'static str = built_info::CFG_ENV;
+    assert!(built_info::CFG_ALL.contains(&("target_os", built_info::CFG_OS)));
     println!("builttestsuccess");
 }}"#,
             features = FEATURES,
@@ -428,6 +440,243 @@ fn main() {
     p.create_and_run(&[]);
 }
 
+#[test]
+fn collection_duration() {
+    let mut p = Project::new();
+    p.bootstrap(None)
+        .add_file(
+            "build.rs",
+            r#"
+fn main() {
+    let dst = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("built.rs");
+    let mut options = built::Options::default();
+    options.set_collection_duration(true);
+    built::write_built_file_with_options(&options, &dst).unwrap();
+}"#,
+        )
+        .add_file(
+            "src/main.rs",
+            r#"
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+fn main() {
+    let _: u64 = built_info::BUILT_COLLECTION_DURATION_MS;
+    println!("builttestsuccess");
+}
+"#,
+        );
+    p.create_and_run(&[]);
+}
+
+#[test]
+fn minimal_options() {
+    let mut p = Project::new();
+    p.bootstrap(None)
+        .add_file(
+            "build.rs",
+            r#"
+fn main() {
+    let dst = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("built.rs");
+    built::write_built_file_with_options(&built::Options::minimal(), &dst).unwrap();
+}"#,
+        )
+        .add_file(
+            "src/main.rs",
+            r#"
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+fn main() {
+    assert_eq!(built_info::PKG_VERSION, "0.0.1");
+    let built_file = include_str!(concat!(env!("OUT_DIR"), "/built.rs"));
+    assert_eq!(built_file.matches("pub static").count(), 1);
+    println!("builttestsuccess");
+}
+"#,
+        );
+    p.create_and_run(&[]);
+}
+
+#[test]
+fn compact() {
+    let mut p = Project::new();
+    p.bootstrap(None)
+        .add_file(
+            "build.rs",
+            r#"
+fn main() {
+    let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    let mut options = built::Options::default();
+    options.set_emit_map(true).set_emit_print_fn(true).set_emit_macro(true);
+    built::write_built_file_with_options(&options, &out_dir.join("built.rs")).unwrap();
+    options.set_compact(true);
+    built::write_built_file_with_options(&options, &out_dir.join("compact.rs")).unwrap();
+
+    let mut vars = built::parse_built_file(&out_dir.join("built.rs")).unwrap();
+    let mut compact_vars = built::parse_built_file(&out_dir.join("compact.rs")).unwrap();
+    for volatile in ["BUILT_COLLECTION_DURATION_MS", "BUILT_MAP", "BUILT_TIME_UTC", "BUILT_TIME_LOCAL"] {
+        vars.remove(volatile);
+        compact_vars.remove(volatile);
+    }
+    assert_eq!(vars, compact_vars);
+
+    let size = std::fs::metadata(out_dir.join("built.rs")).unwrap().len();
+    let compact_size = std::fs::metadata(out_dir.join("compact.rs")).unwrap().len();
+    assert!(compact_size < size);
+}"#,
+        )
+        .add_file(
+            "src/main.rs",
+            r##"
+#[macro_use]
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/compact.rs"));
+}
+
+fn main() {
+    assert_eq!(built_info::PKG_VERSION, "0.0.1");
+    assert_eq!(build_info!(pkg_version), "0.0.1");
+    assert!(built_info::BUILT_MAP.contains(&("PKG_VERSION", "0.0.1")));
+    built_info::print_build_info();
+    let built_file = include_str!(concat!(env!("OUT_DIR"), "/compact.rs"));
+    assert!(!built_file.contains("#[doc"));
+    assert!(!built_file.contains("\n    "));
+    println!("builttestsuccess");
+}
+"##,
+        );
+    p.create_and_run(&[]);
+}
+
+#[test]
+fn rerun_if_changed() {
+    let mut p = Project::new();
+    p.bootstrap(None)
+        .add_file(
+            "build.rs",
+            r#"
+fn main() {
+    let dst = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("built.rs");
+    let mut options = built::Options::default();
+    options.set_rerun_if_changed(true);
+    built::write_built_file_with_options(&options, &dst).unwrap();
+}"#,
+        )
+        .add_file(
+            "src/main.rs",
+            r#"
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+fn main() {
+    let manifest = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+    assert!(built_info::BUILT_RERUN_PATHS.contains(&manifest.to_str().unwrap()));
+    println!("builttestsuccess");
+}
+"#,
+        );
+    p.create_and_run(&[]);
+}
+
+#[test]
+fn intern_strings() {
+    let mut p = Project::new();
+    p.bootstrap(None)
+        .add_file(
+            "build.rs",
+            r#"
+fn main() {
+    let dst = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("built.rs");
+    let mut options = built::Options::default();
+    options.set_intern_strings(true);
+    built::write_built_file_with_options(&options, &dst).unwrap();
+
+    let vars = built::parse_built_file(&dst).unwrap();
+    assert_eq!(vars["PKG_VERSION"], "\"0.0.1\"");
+    assert_eq!(vars["HOST"], vars["TARGET"]);
+    assert!(vars["HOST"].starts_with('"'));
+    assert!(!vars.keys().any(|name| name.starts_with("__BUILT_STR_")));
+}"#,
+        )
+        .add_file(
+            "src/main.rs",
+            r#"
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+fn main() {
+    assert_eq!(built_info::PKG_VERSION, "0.0.1");
+    assert_eq!(built_info::TARGET, built_info::HOST);
+    assert!(!built_info::IS_CROSS_COMPILE);
+    let built_file = include_str!(concat!(env!("OUT_DIR"), "/built.rs"));
+    assert!(built_file.contains("pub static HOST: &str = __BUILT_STR_"));
+    println!("builttestsuccess");
+}
+"#,
+        );
+    p.create_and_run(&[]);
+}
+
+#[test]
+fn collect_map() {
+    let mut p = Project::new();
+    p.bootstrap(None)
+        .add_file(
+            "build.rs",
+            r#"
+fn main() {
+    let map = built::Options::default().collect_map().unwrap();
+    assert_eq!(map["PKG_VERSION"].as_deref(), Some("0.0.1"));
+    assert_eq!(map["PKG_SOURCE"], None);
+    assert_eq!(map["DEBUG"].as_deref(), Some("true"));
+    assert!(map["RUSTC_VERSION"].as_deref().unwrap().starts_with("rustc "));
+    assert!(map.contains_key("OVERRIDE_VARIABLES_USED"));
+}"#,
+        )
+        .add_file(
+            "src/main.rs",
+            r#"
+fn main() {
+    println!("builttestsuccess");
+}
+"#,
+        );
+    p.create_and_run(&[]);
+}
+
+#[test]
+fn env_file() {
+    let mut p = Project::new();
+    p.bootstrap(None)
+        .add_file(
+            "build.rs",
+            r#"
+fn main() {
+    let dst = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("built.env");
+    built::write_built_env(&built::Options::default(), &dst).unwrap();
+}"#,
+        )
+        .add_file(
+            "src/main.rs",
+            r#"
+fn main() {
+    let env_file = include_str!(concat!(env!("OUT_DIR"), "/built.env"));
+    assert!(env_file.lines().any(|l| l == "PKG_VERSION=0.0.1"));
+    assert!(env_file.lines().any(|l| l == "PKG_NAME=testbox"));
+    assert!(env_file.lines().any(|l| l.starts_with("RUSTC_VERSION=\"rustc ")));
+    assert!(!env_file.contains("PKG_SOURCE"));
+    println!("builttestsuccess");
+}
+"#,
+        );
+    p.create_and_run(&[]);
+}
+
 #[test]
 fn simple_workspace() {
     let mut p = Project::new();
@@ -467,54 +716,38 @@ fn main() {
 }
 
 #[test]
-#[cfg(all(
-    feature = "cargo-lock",
-    feature = "dependency-tree",
-    any(feature = "git2", feature = "gix"),
-    feature = "chrono",
-    feature = "semver"
-))]
-fn full_testbox() {
-    for git_feature in git_features() {
-        let mut p = Project::new();
-
-        let built_root = get_built_root();
-
-        #[rustversion::before(1.85)]
-        const FEATURES: &str = r#"["DEFAULT", "MEGAAWESOME", "SUPERAWESOME"]"#;
-        #[rustversion::before(1.85)]
-        const FEATURES_STR: &str = r#""DEFAULT, MEGAAWESOME, SUPERAWESOME""#;
-
-        #[rustversion::since(1.85)]
-        const FEATURES: &str = r#"["MegaAwesome", "SuperAwesome", "default"]"#;
-        #[rustversion::since(1.85)]
-        const FEATURES_STR: &str = r#""MegaAwesome, SuperAwesome, default""#;
+#[cfg(feature = "cargo-metadata")]
+fn cargo_metadata_testbox() {
+    let mut p = Project::new();
+    let built_root = get_built_root();
 
-        p.add_file(
+    p.add_file(
         "Cargo.toml",
         format!(
             r#"
 [package]
 name = "testbox"
-version = "1.2.3-rc1"
-authors = ["Joe", "Bob", "Harry:Potter"]
+version = "0.0.1"
+edition = "2021"
 build = "build.rs"
-description = "xobtset"
-homepage = "localhost"
-repository = "https://dev.example.com/sources/testbox/"
-license = "MIT"
-
-[dependencies]
-built = {{ path = "{built_root}", features=["cargo-lock", "dependency-tree", "{git_feature}", "chrono", "semver"] }}
+categories = ["development-tools", "config"]
+keywords = ["zeta", "alpha"]
+links = "testbox-native"
+default-run = "testbox"
+readme = "README.md"
+documentation = "https://docs.rs/testbox"
 
-[build-dependencies]
-built = {{ path = "{built_root}", features=["cargo-lock", "dependency-tree", "{git_feature}", "chrono", "semver"] }}
+[package.metadata.myapp]
+key = "value"
 
 [features]
-default = ["SuperAwesome", "MegaAwesome"]
-SuperAwesome = []
-MegaAwesome = []"#,
-            built_root = built_root.display().to_string().escape_default()
+default = ["foo"]
+foo = []
+bar = []
+
+[build-dependencies]
+built = {{ path = "{}", features = ["cargo-metadata"] }}"#,
+            built_root.display().to_string().escape_default()
         ),
     )
     .add_file(
@@ -524,23 +757,231 @@ fn main() {
     built::write_built_file().unwrap();
 }"#,
     )
-    .set_env("CONTINUOUS_INTEGRATION", "1")
     .add_file(
         "src/main.rs",
-        format!(r#"
-//! The testbox.
+        r#"
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
 
-mod built_info {{
+fn main() {
+    assert_eq!(built_info::PKG_EDITION, "2021");
+    assert_eq!(built_info::PKG_METADATA, Some("{\"myapp\":{\"key\":\"value\"}}"));
+    assert_eq!(built_info::FEATURES_AVAILABLE, ["bar", "default", "foo"]);
+    assert_eq!(built_info::PKG_CATEGORIES, ["development-tools", "config"]);
+    assert_eq!(built_info::PKG_KEYWORDS, ["zeta", "alpha"]);
+    assert_eq!(built_info::PKG_LINKS, Some("testbox-native"));
+    assert_eq!(built_info::PKG_DEFAULT_RUN, Some("testbox"));
+    assert_eq!(built_info::PKG_README, Some("README.md"));
+    assert_eq!(built_info::PKG_DOCUMENTATION, Some("https://docs.rs/testbox"));
+    println!("builttestsuccess");
+}
+"#,
+    );
+    p.create_and_run(&[]);
+}
+
+#[test]
+#[cfg(all(feature = "cargo-lock", feature = "cargo-metadata"))]
+fn dependencies_resolved() {
+    for args in [&[][..], &["--features", "extra"][..]] {
+        let mut p = Project::new();
+        let built_root = get_built_root();
+
+        p.add_file(
+            "Cargo.toml",
+            format!(
+                r#"
+[package]
+name = "testbox"
+version = "0.0.1"
+edition = "2021"
+build = "build.rs"
+
+[dependencies]
+alpha = {{ path = "alpha" }}
+beta = {{ path = "beta", optional = true }}
+
+[target.'cfg(not(any(unix, windows)))'.dependencies]
+gamma = {{ path = "gamma" }}
+
+[features]
+extra = ["dep:beta"]
+
+[build-dependencies]
+built = {{ path = "{}", features = ["cargo-lock", "cargo-metadata"] }}"#,
+                built_root.display().to_string().escape_default()
+            ),
+        );
+        for dep in ["alpha", "beta", "gamma"] {
+            p.add_file(
+                format!("{dep}/Cargo.toml"),
+                format!("[package]\nname = \"{dep}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n"),
+            )
+            .add_file(format!("{dep}/src/lib.rs"), "");
+        }
+        p.add_file(
+            "build.rs",
+            r#"
+fn main() {
+    let mut options = built::Options::default();
+    options.set_dependencies_resolved(true);
+    let dst = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("built.rs");
+    built::write_built_file_with_options(
+        &options,
+        Some(std::env::var("CARGO_MANIFEST_DIR").unwrap().as_ref()),
+        &dst,
+    )
+    .unwrap();
+}"#,
+        )
+        .add_file(
+            "src/main.rs",
+            r#"
+mod built_info {
     include!(concat!(env!("OUT_DIR"), "/built.rs"));
-}}
+}
 
-fn main() {{
-    assert_eq!(built_info::GIT_VERSION, None);
-    assert_eq!(built_info::GIT_DIRTY, None);
-    assert_eq!(built_info::GIT_COMMIT_HASH, None);
-    assert_eq!(built_info::GIT_COMMIT_HASH_SHORT, None);
-    assert_eq!(built_info::GIT_HEAD_REF, None);
-    assert!(built_info::CI_PLATFORM.is_some());
+fn main() {
+    #[cfg(feature = "extra")]
+    assert_eq!(built_info::DEPENDENCIES, [("alpha", "0.1.0"), ("beta", "0.1.0")]);
+    #[cfg(not(feature = "extra"))]
+    assert_eq!(built_info::DEPENDENCIES, [("alpha", "0.1.0")]);
+    assert_eq!(built_info::DEPENDENCIES_WITH_SOURCE[0], ("alpha", "0.1.0", "path"));
+    println!("builttestsuccess");
+}
+"#,
+        );
+        p.create_and_run(args);
+    }
+}
+
+#[test]
+#[cfg(feature = "cargo-metadata")]
+fn skip_in_ide() {
+    let mut p = Project::new();
+    p.bootstrap(Some("cargo-metadata"))
+        .add_file(
+            "build.rs",
+            r#"
+fn main() {
+    let dst = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("built.rs");
+    let mut options = built::Options::default();
+    options.set_skip_in_ide(true);
+    built::write_built_file_with_options(&options, &dst).unwrap();
+}"#,
+        )
+        .add_file(
+            "src/main.rs",
+            r#"
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+fn main() {
+    assert!(built_info::BUILT_IN_IDE);
+    assert_eq!(built_info::PKG_EDITION, "");
+    assert_eq!(built_info::PKG_METADATA, None);
+    assert!(built_info::FEATURES_AVAILABLE.is_empty());
+    assert!(built_info::PKG_CATEGORIES.is_empty());
+    assert!(built_info::PKG_KEYWORDS.is_empty());
+    assert_eq!(built_info::PKG_LINKS, None);
+    assert_eq!(built_info::PKG_DEFAULT_RUN, None);
+    assert_eq!(built_info::PKG_README, None);
+    assert_eq!(built_info::PKG_DOCUMENTATION, None);
+    println!("builttestsuccess");
+}
+"#,
+        )
+        .set_env("RA_RUSTC_WRAPPER", "1");
+    p.create_and_run(&[]);
+}
+
+#[test]
+#[cfg(all(
+    feature = "cargo-lock",
+    feature = "dependency-tree",
+    any(feature = "git2", feature = "gix"),
+    feature = "chrono",
+    feature = "semver"
+))]
+fn full_testbox() {
+    for git_feature in git_features() {
+        let mut p = Project::new();
+
+        let built_root = get_built_root();
+
+        #[rustversion::before(1.85)]
+        const FEATURES: &str = r#"["DEFAULT", "MEGAAWESOME", "SUPERAWESOME"]"#;
+        #[rustversion::before(1.85)]
+        const FEATURES_STR: &str = r#""DEFAULT, MEGAAWESOME, SUPERAWESOME""#;
+
+        #[rustversion::since(1.85)]
+        const FEATURES: &str = r#"["MegaAwesome", "SuperAwesome", "default"]"#;
+        #[rustversion::since(1.85)]
+        const FEATURES_STR: &str = r#""MegaAwesome, SuperAwesome, default""#;
+
+        p.add_file(
+        "Cargo.toml",
+        format!(
+            r#"
+[package]
+name = "testbox"
+version = "1.2.3-rc1"
+authors = ["Joe", "Bob", "Harry:Potter"]
+build = "build.rs"
+description = "xobtset"
+homepage = "localhost"
+repository = "https://dev.example.com/sources/testbox/"
+license = "MIT"
+
+[dependencies]
+built = {{ path = "{built_root}", features=["cargo-lock", "dependency-tree", "{git_feature}", "chrono", "semver"] }}
+
+[build-dependencies]
+built = {{ path = "{built_root}", features=["cargo-lock", "dependency-tree", "{git_feature}", "chrono", "semver"] }}
+
+[features]
+default = ["SuperAwesome", "MegaAwesome"]
+SuperAwesome = []
+MegaAwesome = []"#,
+            built_root = built_root.display().to_string().escape_default()
+        ),
+    )
+    .add_file(
+        "build.rs",
+        r#"
+fn main() {
+    built::write_built_file().unwrap();
+}"#,
+    )
+    .set_env("CONTINUOUS_INTEGRATION", "1")
+    .add_file(
+        "src/main.rs",
+        format!(r#"
+//! The testbox.
+
+mod built_info {{
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}}
+
+fn main() {{
+    assert_eq!(built_info::GIT_VERSION, None);
+    assert_eq!(built_info::GIT_DIRTY, None);
+    assert_eq!(built_info::GIT_HAS_CONFLICTS, None);
+    assert_eq!(built_info::GIT_BRANCH_COUNT, None);
+    assert_eq!(built_info::GIT_TAG_COUNT, None);
+    assert_eq!(built_info::GIT_COMMIT_HASH, None);
+    assert_eq!(built_info::GIT_COMMIT_HASH_SHORT, None);
+    assert_eq!(built_info::GIT_OBJECT_FORMAT, None);
+    assert_eq!(built_info::GIT_UPSTREAM_REMOTE, None);
+    assert_eq!(built_info::GIT_IS_TAGGED_RELEASE, None);
+    assert_eq!(built_info::GIT_NEAREST_TAG_COMMIT, None);
+    assert_eq!(built_info::GIT_TAG_MESSAGE, None);
+    assert_eq!(built_info::GIT_COMMIT_WEB_URL, None);
+    assert_eq!(built_info::GIT_AVAILABLE, Some(false));
+    assert_eq!(built_info::GIT_HEAD_REF, None);
+    assert!(built_info::CI_PLATFORM.is_some());
     assert_eq!(built_info::PKG_VERSION, "1.2.3-rc1");
     assert_eq!(built_info::PKG_VERSION_MAJOR, "1");
     assert_eq!(built_info::PKG_VERSION_MINOR, "2");
@@ -552,9 +993,14 @@ fn main() {{
     assert_eq!(built_info::PKG_HOMEPAGE, "localhost");
     assert_eq!(built_info::PKG_LICENSE, "MIT");
     assert_eq!(built_info::PKG_REPOSITORY, "https://dev.example.com/sources/testbox/");
+    assert_eq!(built_info::PKG_RUST_VERSION, None);
+    assert_eq!(built_info::PKG_RUST_VERSION_MAJOR, None);
     assert!(built_info::NUM_JOBS > 0);
     assert!(built_info::OPT_LEVEL == "0");
     assert!(built_info::DEBUG);
+    assert!(built_info::DEBUG_ASSERTIONS);
+    assert_eq!(built_info::CFG_PANIC, "unwind");
+    assert!(built_info::CFG_TARGET_HAS_ATOMIC.contains(&"ptr"));
     assert_eq!(built_info::PROFILE, "debug");
     assert_eq!(built_info::FEATURES, {features});
     assert_eq!(built_info::FEATURES_STR, {features_str});
@@ -563,6 +1009,8 @@ fn main() {{
     assert_eq!(built_info::FEATURES_LOWERCASE_STR,
                "default, megaawesome, superawesome");
     assert_ne!(built_info::RUSTC_VERSION, "");
+    assert!(built_info::RUSTC_COMMIT_DATE.is_some());
+    assert!(built_info::RUSTC_RELEASE_DATE >= built_info::RUSTC_COMMIT_DATE);
     assert_ne!(built_info::RUSTDOC_VERSION, "");
     assert_ne!(built_info::DEPENDENCIES_STR, "");
     assert_ne!(built_info::DIRECT_DEPENDENCIES_STR, "");
@@ -578,12 +1026,16 @@ fn main() {{
     assert_ne!(built_info::CFG_POINTER_WIDTH, "");
     // For CFG_ENV, empty string is a possible value.
     let _: &'static str = built_info::CFG_ENV;
+    assert!(built_info::CFG_ALL.contains(&("target_os", built_info::CFG_OS)));
 
     assert!(built::util::parse_versions(built_info::DEPENDENCIES.iter())
         .any(|(name, ver)| name == "toml" && ver >= built::semver::Version::parse("0.1.0").unwrap()));
 
     assert_eq!(built_info::DIRECT_DEPENDENCIES.len(), 1);
     assert_eq!(built_info::DIRECT_DEPENDENCIES[0].0, "built");
+    assert!(built_info::DEPENDENCIES_WITH_SOURCE.iter().any(|(n, _, s)| *n == "built" && *s == "path"));
+    assert!(built_info::DEPENDENCIES_WITH_SOURCE.iter().any(|(n, _, s)| *n == "toml" && *s == "registry"));
+    assert!(!built_info::DEPENDENCIES_TRUNCATED);
 
     assert!((built::chrono::offset::Utc::now() - built::util::strptime(built_info::BUILT_TIME_UTC)).num_days() <= 1);
 
@@ -664,6 +1116,7 @@ fn main() {
             "not_too_fast_not_too_slow",
         )
         .set_env("BUILT_OVERRIDE_testbox_DEBUG", "false")
+        .set_env("BUILT_OVERRIDE_testbox_INCREMENTAL", "true")
         .set_env("BUILT_OVERRIDE_testbox_PROFILE", "MEDIUM")
         .set_env(
             "BUILT_OVERRIDE_testbox_FEATURES",
@@ -671,6 +1124,7 @@ fn main() {
         )
         .set_env("BUILT_OVERRIDE_testbox_RUSTC", "overridec")
         .set_env("BUILT_OVERRIDE_testbox_RUSTC_VERSION", "overridec v1")
+        .set_env("BUILT_OVERRIDE_testbox_RUSTC_COMMIT_DATE", "2020-05-04")
         .set_env("BUILT_OVERRIDE_testbox_RUSTDOC", "overridedoc")
         .set_env("BUILT_OVERRIDE_testbox_RUSTDOC_VERSION", "overridedoc v1")
         .set_env("BUILT_OVERRIDE_testbox_HOST", "overridehost")
@@ -715,6 +1169,7 @@ fn main() {
     assert_eq!(built_info::NUM_JOBS, 999);
     assert_eq!(built_info::OPT_LEVEL, "not_too_fast_not_too_slow");
     assert!(!built_info::DEBUG);
+    assert_eq!(built_info::INCREMENTAL, Some(true));
     assert_eq!(built_info::PROFILE, "MEDIUM");
     assert_eq!(built_info::FEATURES,
                ["Stereo Sound", "cup_holder", "dynamic range"]);
@@ -724,12 +1179,16 @@ fn main() {
                ["cup_holder", "dynamic range", "stereo sound"]);
     assert_eq!(built_info::FEATURES_LOWERCASE_STR,
                "cup_holder, dynamic range, stereo sound");
+    assert_eq!(built_info::FEATURES_BANNER, "cup-holder dynamic range stereo sound");
     assert_eq!(built_info::RUSTC, "overridec");
     assert_eq!(built_info::RUSTC_VERSION, "overridec v1");
+    assert_eq!(built_info::RUSTC_COMMIT_DATE, Some("2020-05-04"));
+    assert_eq!(built_info::RUSTC_RELEASE_DATE, None);
     assert_eq!(built_info::RUSTDOC, "overridedoc");
     assert_eq!(built_info::RUSTDOC_VERSION, "overridedoc v1");
     assert_eq!(built_info::HOST, "overridehost");
     assert_eq!(built_info::TARGET, "potato");
+    assert!(built_info::IS_CROSS_COMPILE);
     assert_eq!(built_info::CFG_TARGET_ARCH, "potatoes");
     assert_eq!(built_info::CFG_ENDIAN, "random");
     assert_eq!(built_info::CFG_FAMILY, "v0");
@@ -755,6 +1214,7 @@ fn main() {
         "GIT_HEAD_REF",
         "GIT_VERSION",
         "HOST",
+        "INCREMENTAL",
         "NUM_JOBS",
         "OPT_LEVEL",
         "PKG_AUTHORS",
@@ -770,6 +1230,7 @@ fn main() {
         "PKG_VERSION_PRE",
         "PROFILE",
         "RUSTC",
+        "RUSTC_COMMIT_DATE",
         "RUSTC_VERSION",
         "RUSTDOC",
         "RUSTDOC_VERSION",
@@ -850,6 +1311,7 @@ fn main() {
             "not_too_fast_not_too_slow",
         )
         .set_env("BUILT_OVERRIDE_great_testbox_DEBUG", "false")
+        .set_env("BUILT_OVERRIDE_great_testbox_INCREMENTAL", "true")
         .set_env("BUILT_OVERRIDE_great_testbox_PROFILE", "MEDIUM")
         .set_env(
             "BUILT_OVERRIDE_great_testbox_FEATURES",
@@ -904,6 +1366,7 @@ fn main() {
     assert_eq!(built_info::NUM_JOBS, 999);
     assert_eq!(built_info::OPT_LEVEL, "not_too_fast_not_too_slow");
     assert!(!built_info::DEBUG);
+    assert_eq!(built_info::INCREMENTAL, Some(true));
     assert_eq!(built_info::PROFILE, "MEDIUM");
     assert_eq!(built_info::FEATURES,
                ["Stereo Sound", "cup_holder", "dynamic range"]);
@@ -919,6 +1382,7 @@ fn main() {
     assert_eq!(built_info::RUSTDOC_VERSION, "overridedoc v1");
     assert_eq!(built_info::HOST, "overridehost");
     assert_eq!(built_info::TARGET, "potato");
+    assert!(built_info::IS_CROSS_COMPILE);
     assert_eq!(built_info::CFG_TARGET_ARCH, "potatoes");
     assert_eq!(built_info::CFG_ENDIAN, "random");
     assert_eq!(built_info::CFG_FAMILY, "v0");
@@ -944,6 +1408,7 @@ fn main() {
         "GIT_HEAD_REF",
         "GIT_VERSION",
         "HOST",
+        "INCREMENTAL",
         "NUM_JOBS",
         "OPT_LEVEL",
         "PKG_AUTHORS",
@@ -966,68 +1431,652 @@ fn main() {
     ]);
     println!("builttestsuccess");
 }"#,
-        );
-        p.create_and_run(&[]);
-    }
+        );
+        p.create_and_run(&[]);
+    }
+}
+
+#[test]
+#[cfg(feature = "chrono")]
+fn source_date_epoch() {
+    let mut p = Project::new();
+    let built_root = get_built_root();
+
+    p.add_file(
+        "Cargo.toml",
+        format!(
+            r#"
+[package]
+name = "testbox"
+version = "1.2.3-rc1"
+authors = ["Joe", "Bob", "Harry:Potter"]
+build = "build.rs"
+description = "xobtset"
+
+[dependencies]
+built = {{ path = "{built_root}", features=["chrono"] }}
+
+[build-dependencies]
+built = {{ path = "{built_root}", features=["chrono"] }}"#,
+            built_root = built_root.display().to_string().escape_default()
+        ),
+    )
+    .add_file(
+        "build.rs",
+        r#"
+fn main() {
+    let dst = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("built.rs");
+    let mut options = built::Options::default();
+    options
+        .set_time_components(true)
+        .set_time_local(true)
+        .set_collection_duration(true);
+    built::write_built_file_with_options(&options, &dst).unwrap();
+}"#,
+    )
+    .add_file(
+        "src/main.rs",
+        r#"
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+fn main() {
+    assert_eq!(built::util::strptime(built_info::BUILT_TIME_UTC).to_rfc2822(),
+              "Sat, 25 May 2024 12:15:59 +0000");
+    assert_eq!(built_info::BUILT_YEAR, 2024);
+    assert_eq!(built_info::BUILT_MONTH, 5);
+    assert_eq!(built_info::BUILT_DAY, 25);
+    assert_eq!(built_info::BUILT_ISO_WEEK, 21);
+    assert_eq!(built_info::BUILT_TIME_LOCAL, Some("Sat, 25 May 2024 12:15:59 +0000"));
+    assert_eq!(built_info::NUM_JOBS, 1);
+    let built_file = include_str!(concat!(env!("OUT_DIR"), "/built.rs"));
+    assert!(!built_file.contains("BUILT_COLLECTION_DURATION_MS"));
+    println!("builttestsuccess");
+}"#,
+    )
+    .set_env("SOURCE_DATE_EPOCH", "1716639359");
+    p.create_and_run(&[]);
+}
+
+#[test]
+#[cfg(any(feature = "git2", feature = "gix"))]
+fn git_no_git() {
+    for git_feature in git_features_for_bootstrap() {
+        // `root` isn't even a git-repo
+        let mut p = Project::new();
+        p.bootstrap(git_feature).add_file(
+            "src/main.rs",
+            r#"
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+fn main() {
+    assert_eq!(built_info::GIT_DIRTY, None);
+    println!("builttestsuccess");
+}
+"#,
+        );
+
+        p.create_and_run(&[]);
+    }
+}
+
+#[test]
+#[cfg(feature = "git2")]
+fn clean_then_dirty_git() {
+    for git_feature in git_features_for_bootstrap() {
+        let mut p = Project::new();
+        p.bootstrap(git_feature).add_file(
+            "src/main.rs",
+            r#"
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+fn main() {
+    assert_eq!(built_info::GIT_DIRTY, Some(false));
+    assert_eq!(built_info::GIT_IS_TAGGED_RELEASE, Some(true));
+    assert_eq!(built_info::GIT_NEAREST_TAG_COMMIT, built_info::GIT_COMMIT_HASH);
+    assert_eq!(built_info::GIT_TAG_MESSAGE, None);
+    assert_eq!(built_info::GIT_AVAILABLE, Some(true));
+    println!("builttestsuccess");
+}
+"#,
+        );
+        let repo = p.init_git();
+        let root = p.create().expect("Creating the project failed");
+
+        let sig = git2::Signature::now("foo", "bar").unwrap();
+
+        let mut idx = repo.index().unwrap();
+        for p in &["src/main.rs", "build.rs"] {
+            idx.add_path(path::Path::new(p)).unwrap();
+        }
+        idx.write().unwrap();
+        let head = repo
+            .commit(
+                Some("HEAD"),
+                &sig,
+                &sig,
+                "Testing testing 1 2 3",
+                &repo.find_tree(idx.write_tree().unwrap()).unwrap(),
+                &[],
+            )
+            .unwrap();
+        repo.tag_lightweight("v1.0.0", &repo.find_object(head, None).unwrap(), false)
+            .unwrap();
+        Project::run(
+            root.as_ref(),
+            &[],
+            std::iter::empty::<(ffi::OsString, ffi::OsString)>(),
+        );
+
+        let mut f = std::fs::OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .open(root.path().join("src/main.rs"))
+            .unwrap();
+        f.write_all(
+            r#"
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+fn main() {
+    assert_eq!(built_info::GIT_DIRTY, Some(true));
+    assert_eq!(built_info::GIT_IS_TAGGED_RELEASE, Some(false));
+    assert!(built_info::GIT_COMMIT_HASH.is_some());
+    assert!(built_info::GIT_COMMIT_HASH_SHORT.is_some());
+    assert!(built_info::GIT_COMMIT_HASH.unwrap().starts_with(built_info::GIT_COMMIT_HASH_SHORT.unwrap()));
+    assert_eq!(built_info::GIT_OBJECT_FORMAT, Some("sha1"));
+    assert_eq!(built_info::GIT_UPSTREAM_REMOTE, None);
+    println!("builttestsuccess");
+}
+"#
+                .as_bytes(),
+        )
+            .unwrap();
+
+        Project::run(
+            root.as_ref(),
+            &[],
+            std::iter::empty::<(ffi::OsString, ffi::OsString)>(),
+        );
+    }
+}
+
+#[test]
+#[cfg(feature = "git2")]
+fn git_root_outside_manifest() {
+    for git_feature in git_features_for_bootstrap() {
+        let repo_root = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(&repo_root).unwrap();
+        let sig = git2::Signature::now("foo", "bar").unwrap();
+        let commit_oid = repo
+            .commit(
+                Some("HEAD"),
+                &sig,
+                &sig,
+                "Testing",
+                &repo
+                    .find_tree(repo.index().unwrap().write_tree().unwrap())
+                    .unwrap(),
+                &[],
+            )
+            .unwrap();
+
+        // The project itself is not a git-repository
+        let mut p = Project::new();
+        p.bootstrap(git_feature)
+            .add_file(
+                "build.rs",
+                r#"
+fn main() {
+    let dst = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("built.rs");
+    let mut options = built::Options::default();
+    options.set_git_root(std::env::var("TESTBOX_GIT_ROOT").unwrap());
+    built::write_built_file_with_options(
+        &options,
+        Some(std::env::var("CARGO_MANIFEST_DIR").unwrap().as_ref()),
+        &dst,
+    )
+    .unwrap();
+}"#,
+            )
+            .add_file(
+                "src/main.rs",
+                format!(
+                    r#"
+mod built_info {{
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}}
+
+fn main() {{
+    assert_eq!(built_info::GIT_COMMIT_HASH, Some("{commit_oid}"));
+    println!("builttestsuccess");
+}}
+"#
+                ),
+            )
+            .set_env("TESTBOX_GIT_ROOT", repo_root.path());
+        p.create_and_run(&[]);
+    }
+}
+
+#[test]
+#[cfg(feature = "git2")]
+fn empty_git() {
+    for git_feature in git_features_for_bootstrap() {
+        // Issue #7, git can be there and still fail
+        let mut p = Project::new();
+        p.bootstrap(git_feature).add_file(
+            "src/main.rs",
+            r#"
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+fn main() {
+    println!("builttestsuccess");
+}
+"#,
+        );
+        p.init_git();
+        p.create_and_run(&[]);
+    }
+}
+
+#[cfg(target_os = "windows")]
+#[test]
+fn absolute_paths() {
+    // Issue #35. Usually binaries we refer to are simply executables names but sometimes they are
+    // absolute paths, containing backslashes, and everything gets sad on this devilish platform.
+
+    for git_feature in git_features_for_bootstrap() {
+        let mut p = Project::new();
+        p.bootstrap(git_feature).add_file(
+            "src/main.rs",
+            r#"
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+fn main() {
+    println!("builttestsuccess");
+}
+"#,
+        );
+
+        let rustc_exe_buf = String::from_utf8(
+            process::Command::new("where")
+                .arg("rustc")
+                .output()
+                .expect("Unable to locate absolute path to rustc using `where`")
+                .stdout,
+        )
+        .unwrap();
+        let rustc_exe = rustc_exe_buf.split("\r\n").next().unwrap();
+
+        // There should at least be `C:\`
+        assert!(rustc_exe.contains('\\'));
+
+        let root = p.create().expect("Creating the project failed");
+        let cargo_result = process::Command::new("cargo")
+            .current_dir(&root)
+            .arg("run")
+            .env("RUSTC", &rustc_exe)
+            .output()
+            .expect("cargo failed");
+        if !cargo_result.status.success() {
+            panic!(
+                "cargo failed with {}",
+                String::from_utf8_lossy(&cargo_result.stderr)
+            );
+        }
+    }
+}
+
+/// Returns an iterator over the enabled git features ("gix" and/or "git2").
+fn git_features() -> impl Iterator<Item = &'static str> {
+    cfg!(feature = "gix")
+        .then_some("gix")
+        .into_iter()
+        .chain(cfg!(feature = "git2").then_some("git2"))
+}
+
+// Produce only `Some("git2"|"gix")` or `None` if there is not a single Git feature set.
+fn git_features_for_bootstrap() -> Vec<Option<&'static str>> {
+    let mut features = git_features().peekable();
+    if features.peek().is_none() {
+        vec![None]
+    } else {
+        features.map(Some).collect()
+    }
+}
+
+#[test]
+fn unwrap_known_values() {
+    let mut p = Project::new();
+    p.bootstrap(None)
+        .add_file(
+            "build.rs",
+            r#"
+fn main() {
+    let dst = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("built.rs");
+    let mut options = built::Options::default();
+    options.set_unwrap_known_values(true);
+    built::write_built_file_with_options(&options, &dst).unwrap();
+}"#,
+        )
+        .add_file(
+            "src/main.rs",
+            r#"
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+fn main() {
+    let ci: &str = built_info::CI_PLATFORM;
+    assert_eq!(ci, "SomeCI");
+    let source: Option<&str> = built_info::PKG_SOURCE;
+    assert_eq!(source, None);
+    println!("builttestsuccess");
+}
+"#,
+        )
+        .set_env("BUILT_OVERRIDE_testbox_CI_PLATFORM", "SomeCI");
+    p.create_and_run(&[]);
+}
+
+#[test]
+fn cow_strings() {
+    let mut p = Project::new();
+    p.bootstrap(None)
+        .add_file(
+            "build.rs",
+            r#"
+fn main() {
+    let dst = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("built.rs");
+    let mut options = built::Options::default();
+    options.set_cow_strings(true).set_intern_strings(true);
+    built::write_built_file_with_options(&options, &dst).unwrap();
+}"#,
+        )
+        .add_file(
+            "src/main.rs",
+            r#"
+use std::borrow::Cow;
+
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+fn main() {
+    let mut version: Cow<'static, str> = built_info::PKG_VERSION.clone();
+    assert!(matches!(version, Cow::Borrowed("0.0.1")));
+    version = Cow::Owned(format!("{version}-test"));
+    assert_eq!(version, "0.0.1-test");
+    let ci: Option<Cow<'static, str>> = built_info::CI_PLATFORM.clone();
+    assert_eq!(ci.as_deref(), Some("SomeCI"));
+    assert_eq!(built_info::PKG_SOURCE, None);
+    assert_eq!(built_info::TARGET, built_info::HOST);
+    assert!(!built_info::IS_CROSS_COMPILE);
+    let _features: &[&str] = &built_info::FEATURES;
+    println!("builttestsuccess");
+}
+"#,
+        )
+        .set_env("BUILT_OVERRIDE_testbox_CI_PLATFORM", "SomeCI");
+    p.create_and_run(&[]);
+}
+
+#[test]
+fn emit_print_fn() {
+    let mut p = Project::new();
+    p.bootstrap(None)
+        .add_file(
+            "build.rs",
+            r#"
+fn main() {
+    let dst = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("built.rs");
+    let mut options = built::Options::default();
+    options.set_emit_print_fn(true).set_cow_strings(true);
+    built::write_built_file_with_options(&options, &dst).unwrap();
+}"#,
+        )
+        .add_file(
+            "src/main.rs",
+            r#"
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+fn main() {
+    built_info::print_build_info();
+    println!("builttestsuccess");
+}
+"#,
+        )
+        .set_env("BUILT_OVERRIDE_testbox_CI_PLATFORM", "SomeCI");
+    let (_, stdout) = p.create_and_run(&[]);
+    assert!(stdout.contains("\nPKG_VERSION: \"0.0.1\"\n"));
+    assert!(stdout.starts_with("CI_PLATFORM: Some(\"SomeCI\")\n"));
+    assert!(stdout.contains("\nFEATURES: ["));
+}
+
+#[test]
+fn emit_map() {
+    let mut p = Project::new();
+    p.bootstrap(None)
+        .add_file(
+            "build.rs",
+            r#"
+fn main() {
+    let dst = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("built.rs");
+    let mut options = built::Options::default();
+    options.set_emit_map(true).set_intern_strings(true);
+    built::write_built_file_with_options(&options, &dst).unwrap();
+}"#,
+        )
+        .add_file(
+            "src/main.rs",
+            r#"
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+fn main() {
+    let get = |name| built_info::BUILT_MAP.iter().find(|(n, _)| *n == name).map(|(_, v)| *v);
+    assert_eq!(get("PKG_VERSION"), Some("0.0.1"));
+    assert_eq!(get("CI_PLATFORM"), Some("SomeCI"));
+    assert_eq!(get("DEBUG"), Some("true"));
+    assert_eq!(get("PKG_SOURCE"), None);
+    assert_eq!(get("TARGET"), Some(built_info::TARGET));
+    println!("builttestsuccess");
+}
+"#,
+        )
+        .set_env("BUILT_OVERRIDE_testbox_CI_PLATFORM", "SomeCI");
+    p.create_and_run(&[]);
+}
+
+#[test]
+fn emit_macro() {
+    let mut p = Project::new();
+    p.bootstrap(None)
+        .add_file(
+            "build.rs",
+            r#"
+fn main() {
+    let dst = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("built.rs");
+    let mut options = built::Options::default();
+    options.set_emit_macro(true).set_intern_strings(true);
+    built::write_built_file_with_options(&options, &dst).unwrap();
+}"#,
+        )
+        .add_file(
+            "src/main.rs",
+            r#"
+#[macro_use]
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+fn main() {
+    assert_eq!(build_info!(pkg_version), "0.0.1");
+    assert_eq!(build_info!(pkg_version), built_info::PKG_VERSION);
+    assert_eq!(build_info!(ci_platform), Some("SomeCI"));
+    assert_eq!(build_info!(pkg_source), None);
+    assert_eq!(build_info!(features), built_info::FEATURES);
+    assert!(build_info!(debug));
+    println!("builttestsuccess");
+}
+"#,
+        )
+        .set_env("BUILT_OVERRIDE_testbox_CI_PLATFORM", "SomeCI");
+    p.create_and_run(&[]);
+}
+
+#[test]
+fn no_unused_warnings() {
+    let mut p = Project::new();
+    p.bootstrap(None)
+        .add_file(
+            "build.rs",
+            r#"
+fn main() {
+    let dst = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("built.rs");
+    let mut options = built::Options::default();
+    options
+        .set_intern_strings(true)
+        .set_emit_map(true)
+        .set_emit_print_fn(true)
+        .set_emit_macro(true);
+    built::write_built_file_with_options(&options, &dst).unwrap();
+}"#,
+        )
+        .add_file(
+            "src/main.rs",
+            r#"
+#![deny(dead_code, unused_macros)]
+
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+fn main() {
+    assert_eq!(built_info::PKG_VERSION, "0.0.1");
+    println!("builttestsuccess");
+}
+"#,
+        );
+    p.create_and_run(&[]);
+}
+
+#[test]
+fn build_channel() {
+    let mut p = Project::new();
+    p.bootstrap(None)
+        .add_file(
+            "build.rs",
+            r#"
+fn main() {
+    let dst = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("built.rs");
+    let mut options = built::Options::default();
+    options.set_build_channel_from_env("TESTBOX_CHANNEL");
+    built::write_built_file_with_options(&options, &dst).unwrap();
+}"#,
+        )
+        .add_file(
+            "src/main.rs",
+            r#"
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+fn main() {
+    assert_eq!(built_info::BUILD_CHANNEL, "canary");
+    println!("builttestsuccess");
+}
+"#,
+        )
+        .set_env("TESTBOX_CHANNEL", "canary");
+    p.create_and_run(&[]);
 }
 
 #[test]
-#[cfg(feature = "chrono")]
-fn source_date_epoch() {
+fn enable_if_feature() {
     let mut p = Project::new();
     let built_root = get_built_root();
-
-    p.add_file(
-        "Cargo.toml",
-        format!(
-            r#"
+    p.bootstrap(None)
+        .add_file(
+            "Cargo.toml",
+            format!(
+                r#"
 [package]
 name = "testbox"
-version = "1.2.3-rc1"
-authors = ["Joe", "Bob", "Harry:Potter"]
+version = "0.0.1"
 build = "build.rs"
-description = "xobtset"
 
-[dependencies]
-built = {{ path = "{built_root}", features=["chrono"] }}
+[features]
+default = ["build-stats"]
+build-stats = []
+other = []
 
 [build-dependencies]
-built = {{ path = "{built_root}", features=["chrono"] }}"#,
-            built_root = built_root.display().to_string().escape_default()
-        ),
-    )
-    .add_file(
-        "build.rs",
-        r#"
+built = {{ path = "{}" }}"#,
+                built_root.display().to_string().escape_default()
+            ),
+        )
+        .add_file(
+            "build.rs",
+            r#"
 fn main() {
-    built::write_built_file().unwrap();
+    let dst = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("built.rs");
+    let mut options = built::Options::default();
+    options
+        .enable_if_feature("build-stats", |o| {
+            o.set_build_channel("enabled");
+        })
+        .enable_if_feature("other", |o| {
+            o.set_build_channel("disabled");
+        });
+    built::write_built_file_with_options(&options, &dst).unwrap();
 }"#,
-    )
-    .add_file(
-        "src/main.rs",
-        r#"
+        )
+        .add_file(
+            "src/main.rs",
+            r#"
 mod built_info {
     include!(concat!(env!("OUT_DIR"), "/built.rs"));
 }
 
 fn main() {
-    assert_eq!(built::util::strptime(built_info::BUILT_TIME_UTC).to_rfc2822(),
-              "Sat, 25 May 2024 12:15:59 +0000");
-    assert_eq!(built_info::NUM_JOBS, 1);
+    assert_eq!(built_info::BUILD_CHANNEL, "enabled");
     println!("builttestsuccess");
-}"#,
-    )
-    .set_env("SOURCE_DATE_EPOCH", "1716639359");
+}
+"#,
+        );
     p.create_and_run(&[]);
 }
 
 #[test]
-#[cfg(any(feature = "git2", feature = "gix"))]
-fn git_no_git() {
-    for git_feature in git_features_for_bootstrap() {
-        // `root` isn't even a git-repo
-        let mut p = Project::new();
-        p.bootstrap(git_feature).add_file(
+fn build_os() {
+    let mut p = Project::new();
+    p.bootstrap(None)
+        .add_file(
+            "build.rs",
+            r#"
+fn main() {
+    let dst = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("built.rs");
+    let mut options = built::Options::default();
+    options.set_build_os(true);
+    built::write_built_file_with_options(&options, &dst).unwrap();
+}"#,
+        )
+        .add_file(
             "src/main.rs",
             r#"
 mod built_info {
@@ -1035,22 +2084,33 @@ mod built_info {
 }
 
 fn main() {
-    assert_eq!(built_info::GIT_DIRTY, None);
+    assert_eq!(built_info::BUILD_OS, std::env::consts::OS);
+    assert_eq!(built_info::BUILD_OS_VERSION, Some("Testbox OS 1.0"));
+    assert_eq!(built_info::BUILD_ARCH, std::env::consts::ARCH);
+    assert!(built_info::BUILD_CPU_COUNT >= 1);
     println!("builttestsuccess");
 }
 "#,
-        );
-
-        p.create_and_run(&[]);
-    }
+        )
+        .set_env("BUILT_OVERRIDE_testbox_BUILD_OS_VERSION", "Testbox OS 1.0");
+    p.create_and_run(&[]);
 }
 
 #[test]
-#[cfg(feature = "git2")]
-fn clean_then_dirty_git() {
-    for git_feature in git_features_for_bootstrap() {
-        let mut p = Project::new();
-        p.bootstrap(git_feature).add_file(
+fn rustc_sysroot() {
+    let mut p = Project::new();
+    p.bootstrap(None)
+        .add_file(
+            "build.rs",
+            r#"
+fn main() {
+    let dst = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("built.rs");
+    let mut options = built::Options::default();
+    options.set_rustc_sysroot(true);
+    built::write_built_file_with_options(&options, &dst).unwrap();
+}"#,
+        )
+        .add_file(
             "src/main.rs",
             r#"
 mod built_info {
@@ -1058,74 +2118,70 @@ mod built_info {
 }
 
 fn main() {
-    assert_eq!(built_info::GIT_DIRTY, Some(false));
+    let sysroot = std::path::Path::new(built_info::RUSTC_SYSROOT.unwrap());
+    assert!(sysroot.join("lib").join("rustlib").is_dir());
     println!("builttestsuccess");
 }
 "#,
         );
-        let repo = p.init_git();
-        let root = p.create().expect("Creating the project failed");
-
-        let sig = git2::Signature::now("foo", "bar").unwrap();
-
-        let mut idx = repo.index().unwrap();
-        for p in &["src/main.rs", "build.rs"] {
-            idx.add_path(path::Path::new(p)).unwrap();
-        }
-        idx.write().unwrap();
-        repo.commit(
-            Some("HEAD"),
-            &sig,
-            &sig,
-            "Testing testing 1 2 3",
-            &repo.find_tree(idx.write_tree().unwrap()).unwrap(),
-            &[],
-        )
-        .unwrap();
-        Project::run(
-            root.as_ref(),
-            &[],
-            std::iter::empty::<(ffi::OsString, ffi::OsString)>(),
-        );
+    p.create_and_run(&[]);
+}
 
-        let mut f = std::fs::OpenOptions::new()
-            .write(true)
-            .truncate(true)
-            .open(root.path().join("src/main.rs"))
-            .unwrap();
-        f.write_all(
-            r#"
+#[test]
+#[cfg(any(feature = "git2", feature = "gix"))]
+fn fail_if_dirty() {
+    for git_feature in git_features() {
+        let mut p = Project::new();
+        p.bootstrap(Some(git_feature))
+            .add_file(
+                "build.rs",
+                r#"
+fn main() {
+    let src = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let dst = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("built.rs");
+    let mut options = built::Options::default();
+    options.set_fail_if_dirty(true);
+    let err = built::write_built_file_with_options(&options, Some(src.as_ref()), &dst)
+        .unwrap_err();
+    assert!(err.to_string().contains("is dirty"));
+    assert!(!dst.exists());
+
+    options.set_fail_if_dirty(false);
+    built::write_built_file_with_options(&options, Some(src.as_ref()), &dst).unwrap();
+}"#,
+            )
+            .add_file(
+                "src/main.rs",
+                r#"
 mod built_info {
     include!(concat!(env!("OUT_DIR"), "/built.rs"));
 }
 
 fn main() {
     assert_eq!(built_info::GIT_DIRTY, Some(true));
-    assert!(built_info::GIT_COMMIT_HASH.is_some());
-    assert!(built_info::GIT_COMMIT_HASH_SHORT.is_some());
-    assert!(built_info::GIT_COMMIT_HASH.unwrap().starts_with(built_info::GIT_COMMIT_HASH_SHORT.unwrap()));
     println!("builttestsuccess");
 }
-"#
-                .as_bytes(),
-        )
-            .unwrap();
-
-        Project::run(
-            root.as_ref(),
-            &[],
-            std::iter::empty::<(ffi::OsString, ffi::OsString)>(),
-        );
+"#,
+            )
+            .set_env("BUILT_OVERRIDE_testbox_GIT_DIRTY", "true");
+        p.create_and_run(&[]);
     }
 }
 
 #[test]
-#[cfg(feature = "git2")]
-fn empty_git() {
-    for git_feature in git_features_for_bootstrap() {
-        // Issue #7, git can be there and still fail
-        let mut p = Project::new();
-        p.bootstrap(git_feature).add_file(
+fn line_endings() {
+    let mut p = Project::new();
+    p.bootstrap(None)
+        .add_file(
+            "build.rs",
+            r#"
+fn main() {
+    let dst = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("built.rs");
+    built::write_built_file_with_options(&built::Options::default(), &dst).unwrap();
+    assert!(!std::fs::read_to_string(&dst).unwrap().contains('\r'));
+}"#,
+        )
+        .add_file(
             "src/main.rs",
             r#"
 mod built_info {
@@ -1133,24 +2189,32 @@ mod built_info {
 }
 
 fn main() {
+    assert_eq!(built_info::PKG_DESCRIPTION, "first\r\nsecond");
     println!("builttestsuccess");
 }
 "#,
-        );
-        p.init_git();
-        p.create_and_run(&[]);
-    }
+        )
+        .set_env("BUILT_OVERRIDE_testbox_PKG_DESCRIPTION", "first\r\nsecond");
+    p.create_and_run(&[]);
 }
 
-#[cfg(target_os = "windows")]
 #[test]
-fn absolute_paths() {
-    // Issue #35. Usually binaries we refer to are simply executables names but sometimes they are
-    // absolute paths, containing backslashes, and everything gets sad on this devilish platform.
-
-    for git_feature in git_features_for_bootstrap() {
-        let mut p = Project::new();
-        p.bootstrap(git_feature).add_file(
+fn add_variable() {
+    let mut p = Project::new();
+    p.bootstrap(None)
+        .add_file(
+            "build.rs",
+            r#"
+fn main() {
+    let dst = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("built.rs");
+    let mut options = built::Options::minimal();
+    options
+        .add_variable("BUILD_HOST", "&str", "\"ci-runner-7\"", "The machine that built us.")
+        .add_variable("BUILD_NUMBER", "Option<u32>", "Some(42)", "The build-number.");
+    built::write_built_file_with_options(&options, &dst).unwrap();
+}"#,
+        )
+        .add_file(
             "src/main.rs",
             r#"
 mod built_info {
@@ -1158,54 +2222,47 @@ mod built_info {
 }
 
 fn main() {
+    assert_eq!(built_info::BUILD_HOST, "ci-runner-7");
+    assert_eq!(built_info::BUILD_NUMBER, Some(42));
     println!("builttestsuccess");
 }
 "#,
         );
+    p.create_and_run(&[]);
+}
 
-        let rustc_exe_buf = String::from_utf8(
-            process::Command::new("where")
-                .arg("rustc")
-                .output()
-                .expect("Unable to locate absolute path to rustc using `where`")
-                .stdout,
+#[test]
+fn duplicate_variable() {
+    let mut p = Project::new();
+    p.bootstrap(None)
+        .add_file(
+            "build.rs",
+            r#"
+fn main() {
+    let dst = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("built.rs");
+    let mut options = built::Options::minimal();
+    options.add_variable("PKG_VERSION", "&str", "\"0.0.0\"", "Clashes with `built`.");
+    let err = built::write_built_file_with_options(&options, &dst).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "The variable `PKG_VERSION` is written more than once, e.g. via \
+        `Options::add_variable()` or `Options::add_named_git_source()`"
+    );
+    assert!(!dst.exists());
+    std::fs::write(&dst, "").unwrap();
+}"#,
         )
-        .unwrap();
-        let rustc_exe = rustc_exe_buf.split("\r\n").next().unwrap();
-
-        // There should at least be `C:\`
-        assert!(rustc_exe.contains('\\'));
-
-        let root = p.create().expect("Creating the project failed");
-        let cargo_result = process::Command::new("cargo")
-            .current_dir(&root)
-            .arg("run")
-            .env("RUSTC", &rustc_exe)
-            .output()
-            .expect("cargo failed");
-        if !cargo_result.status.success() {
-            panic!(
-                "cargo failed with {}",
-                String::from_utf8_lossy(&cargo_result.stderr)
-            );
-        }
-    }
+        .add_file(
+            "src/main.rs",
+            r#"
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
 }
 
-/// Returns an iterator over the enabled git features ("gix" and/or "git2").
-fn git_features() -> impl Iterator<Item = &'static str> {
-    cfg!(feature = "gix")
-        .then_some("gix")
-        .into_iter()
-        .chain(cfg!(feature = "git2").then_some("git2"))
+fn main() {
+    println!("builttestsuccess");
 }
-
-// Produce only `Some("git2"|"gix")` or `None` if there is not a single Git feature set.
-fn git_features_for_bootstrap() -> Vec<Option<&'static str>> {
-    let mut features = git_features().peekable();
-    if features.peek().is_none() {
-        vec![None]
-    } else {
-        features.map(Some).collect()
-    }
+"#,
+        );
+    p.create_and_run(&[]);
 }